@@ -1,28 +1,57 @@
 use aoc2024 as aoc_lib;
+use aoc_lib::day::Day;
 use criterion::{criterion_group, criterion_main, Criterion};
 
+/// The environment variable that picks the input directory (defaults to input)
+const INPUT_VAR: &str = "AOC_INPUT";
+
+fn input_dir() -> String {
+  std::env::var(INPUT_VAR).unwrap_or_else(|_| String::from("input"))
+}
+
 macro_rules! benchmarks_internal {
   ( $($day:ident),* ) => {
     paste::paste!{
       $(fn [<$day _benchmark>](c: &mut Criterion) {
-          use aoc_lib::$day;
-          let input_data = aoc_lib::utils::read_inputs("input", &vec![stringify!($day)])
-            .expect("can't read input");
-          let input = $day::generator(&input_data[0]);
+          use aoc_lib::$day::Solution;
+          let input_data = match aoc_lib::utils::read_inputs(&input_dir(), &[stringify!($day)]) {
+            Ok(mut data) => data.remove(0),
+            Err(e) => {
+              eprintln!("Skipping {}: {}", stringify!($day), e);
+              return;
+            }
+          };
+          let input = match Solution::parse(&input_data) {
+            Ok(input) => input,
+            Err(e) => {
+              eprintln!("Skipping {}: {}", stringify!($day), e);
+              return;
+            }
+          };
           c.bench_function(concat!(stringify!($day), " gen"), |b| {
-            b.iter(|| $day::generator(&input_data[0]))
+            b.iter(|| Solution::generator(&input_data))
           });
           c.bench_function(concat!(stringify!($day), " part 1"), |b| {
-            b.iter(|| $day::part1(&input))
+            b.iter(|| Solution::part1(&input))
           });
           c.bench_function(concat!(stringify!($day), " part 2"), |b| {
-            b.iter(|| $day::part2(&input))
+            b.iter(|| Solution::part2(&input))
           });
         }
         criterion_group!($day, [<$day _benchmark>]);
       )*
 
-      criterion_main!($($day),*);
+      // Fail loudly when a day is added to the crate but not benchmarked here
+      fn coverage(_: &mut Criterion) {
+        let benched = [$(stringify!($day)),*];
+        let missing: Vec<&str> = aoc_lib::NAMES.iter().copied()
+          .filter(|name| !benched.contains(name))
+          .collect();
+        assert!(missing.is_empty(), "add {:?} to benchmarks!", missing);
+      }
+      criterion_group!(coverage_check, coverage);
+
+      criterion_main!(coverage_check, $($day),*);
     }
   };
 }
@@ -34,4 +63,4 @@ macro_rules! benchmarks {
   }
 }
 
-benchmarks!(1);
\ No newline at end of file
+benchmarks!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23);
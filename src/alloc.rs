@@ -0,0 +1,166 @@
+//! Optional accounting of heap allocations. With the `alloc-stats` feature a
+//! counting allocator is installed as the global allocator, and each phase of
+//! a day reports how much it allocated. The counters are kept per thread,
+//! which works because every day runs on its own thread.
+
+use serde::Serialize;
+use std::fmt;
+
+/// The allocations made while running one phase of a day.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct AllocStats {
+  /// The number of allocations, including reallocations, per run
+  pub count: u64,
+  /// The bytes requested by those allocations per run
+  pub bytes: u64,
+  /// The most bytes that were live at once, above what was live at the start
+  pub peak_bytes: u64,
+}
+
+/// Is the counting allocator installed?
+pub fn enabled() -> bool {
+  cfg!(feature = "alloc-stats")
+}
+
+/// Format a number of bytes with a binary unit.
+fn pretty_bytes(bytes: u64) -> String {
+  const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+  let mut value = bytes as f64;
+  let mut unit = 0;
+  while value >= 1024.0 && unit < UNITS.len() - 1 {
+    value /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{bytes}B")
+  } else {
+    format!("{value:.2}{}", UNITS[unit])
+  }
+}
+
+impl fmt::Display for AllocStats {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "[{} allocs {} peak {}]", self.count, pretty_bytes(self.bytes),
+           pretty_bytes(self.peak_bytes))
+  }
+}
+
+#[cfg(feature = "alloc-stats")]
+mod counting {
+  use std::alloc::{GlobalAlloc, Layout, System};
+  use std::cell::Cell;
+
+  thread_local! {
+    // Const initialized without destructors, so the allocator may use them
+    static COUNT: Cell<u64> = const { Cell::new(0) };
+    static BYTES: Cell<u64> = const { Cell::new(0) };
+    static LIVE: Cell<i64> = const { Cell::new(0) };
+    static PEAK: Cell<i64> = const { Cell::new(0) };
+  }
+
+  /// Pass everything on to the system allocator, counting as we go.
+  pub struct CountingAllocator;
+
+  fn record(allocated: usize, freed: usize) {
+    let _ = LIVE.try_with(|live| {
+      live.set(live.get() + allocated as i64 - freed as i64);
+      let _ = PEAK.try_with(|peak| peak.set(peak.get().max(live.get())));
+    });
+    if allocated > 0 {
+      let _ = COUNT.try_with(|count| count.set(count.get() + 1));
+      let _ = BYTES.try_with(|bytes| bytes.set(bytes.get() + allocated as u64));
+    }
+  }
+
+  unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+      record(layout.size(), 0);
+      System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+      record(layout.size(), 0);
+      System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+      record(0, layout.size());
+      System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+      record(new_size, layout.size());
+      System.realloc(ptr, layout, new_size)
+    }
+  }
+
+  #[global_allocator]
+  static GLOBAL: CountingAllocator = CountingAllocator;
+
+  /// The thread's counters when a measurement started.
+  pub struct Start {
+    count: u64,
+    bytes: u64,
+    live: i64,
+  }
+
+  pub fn start() -> Start {
+    let live = LIVE.with(Cell::get);
+    PEAK.with(|peak| peak.set(live));
+    Start { count: COUNT.with(Cell::get), bytes: BYTES.with(Cell::get), live }
+  }
+
+  pub fn finish(start: &Start) -> (u64, u64, u64) {
+    (COUNT.with(Cell::get) - start.count, BYTES.with(Cell::get) - start.bytes,
+     (PEAK.with(Cell::get) - start.live).max(0) as u64)
+  }
+}
+
+/// Run func, returning its result and the allocations made on this thread
+/// while it ran divided by the number of runs that func made, or None when
+/// the counting allocator isn't installed.
+pub fn measure<T>(runs: usize, func: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+  #[cfg(feature = "alloc-stats")]
+  {
+    let start = counting::start();
+    let result = func();
+    let (count, bytes, peak_bytes) = counting::finish(&start);
+    let runs = runs.max(1) as u64;
+    (result, Some(AllocStats { count: count / runs, bytes: bytes / runs, peak_bytes }))
+  }
+  #[cfg(not(feature = "alloc-stats"))]
+  {
+    let _ = runs;
+    (func(), None)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{measure, pretty_bytes, AllocStats};
+
+  #[test]
+  fn test_pretty_bytes() {
+    assert_eq!("512B", pretty_bytes(512));
+    assert_eq!("1.50KiB", pretty_bytes(1536));
+    assert_eq!("3.00MiB", pretty_bytes(3 << 20));
+    assert_eq!("[2 allocs 2.00KiB peak 1.00KiB]",
+               AllocStats { count: 2, bytes: 2048, peak_bytes: 1024 }.to_string());
+  }
+
+  #[test]
+  fn test_measure() {
+    let (sum, stats) = measure(2, || {
+      (0..2).map(|_| (0..1000u64).collect::<Vec<_>>().iter().sum::<u64>()).sum::<u64>()
+    });
+    assert_eq!(999_000, sum);
+    if cfg!(feature = "alloc-stats") {
+      let stats = stats.unwrap();
+      assert_eq!(1, stats.count);
+      assert_eq!(8000, stats.bytes);
+      assert_eq!(8000, stats.peak_bytes);
+    } else {
+      assert_eq!(None, stats);
+    }
+  }
+}
@@ -0,0 +1,179 @@
+//! The answer of a part, kept as the kind of value the day computed rather
+//! than as text, so that answers.yml can hold numbers as numbers and a check
+//! compares a number with a number.
+
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// The value a part computed. The constructors normalize, so that the same
+/// value always has the same form: a number that fits in an i64 is an Int
+/// whatever type it was computed in, and text with a line break is Multiline.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Answer {
+  Int(i64),
+  /// A number too large for an Int
+  BigInt(i128),
+  Text(String),
+  /// Text over several lines, such as a picture drawn on a grid, without
+  /// trailing spaces or blank lines at the end
+  Multiline(String),
+}
+
+impl Answer {
+  /// Make an answer from the text given for an example, which is a number
+  /// if it reads as one.
+  pub fn parse(text: &str) -> Self {
+    match text.parse::<i128>() {
+      Ok(n) => Answer::from(n),
+      Err(_) => Answer::from(text),
+    }
+  }
+
+  /// The answer as it would be written in source: a number as it is, text
+  /// quoted, so that 12 and "12" can be told apart in messages.
+  pub fn literal(&self) -> String {
+    match self {
+      Answer::Int(n) => n.to_string(),
+      Answer::BigInt(n) => n.to_string(),
+      Answer::Text(s) | Answer::Multiline(s) => format!("{s:?}"),
+    }
+  }
+}
+
+impl fmt::Display for Answer {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Answer::Int(n) => write!(f, "{n}"),
+      Answer::BigInt(n) => write!(f, "{n}"),
+      Answer::Text(s) | Answer::Multiline(s) => write!(f, "{s}"),
+    }
+  }
+}
+
+impl From<i128> for Answer {
+  fn from(n: i128) -> Self {
+    i64::try_from(n).map_or(Answer::BigInt(n), Answer::Int)
+  }
+}
+
+impl From<u128> for Answer {
+  fn from(n: u128) -> Self {
+    i128::try_from(n).map_or_else(|_| Answer::Text(n.to_string()), Answer::from)
+  }
+}
+
+macro_rules! answer_from_int {
+  ($($t:ty),*) => {
+    $(impl From<$t> for Answer {
+      fn from(n: $t) -> Self {
+        Answer::from(n as i128)
+      }
+    })*
+  };
+}
+
+answer_from_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl From<&str> for Answer {
+  fn from(s: &str) -> Self {
+    if s.contains('\n') {
+      let lines: Vec<&str> = s.lines().map(str::trim_end).collect();
+      Answer::Multiline(lines.join("\n").trim_end_matches('\n').to_string())
+    } else {
+      Answer::Text(s.to_string())
+    }
+  }
+}
+
+impl From<String> for Answer {
+  fn from(s: String) -> Self {
+    if s.contains('\n') { Answer::from(s.as_str()) } else { Answer::Text(s) }
+  }
+}
+
+impl Serialize for Answer {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    match self {
+      Answer::Int(n) => serializer.serialize_i64(*n),
+      Answer::BigInt(n) => serializer.serialize_i128(*n),
+      Answer::Text(s) | Answer::Multiline(s) => serializer.serialize_str(s),
+    }
+  }
+}
+
+/// Reads a number or a string, where a quoted number stays text.
+struct AnswerVisitor;
+
+impl Visitor<'_> for AnswerVisitor {
+  type Value = Answer;
+
+  fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "an integer or a string")
+  }
+
+  fn visit_i64<E: de::Error>(self, n: i64) -> Result<Answer, E> {
+    Ok(Answer::from(n))
+  }
+
+  fn visit_u64<E: de::Error>(self, n: u64) -> Result<Answer, E> {
+    Ok(Answer::from(n))
+  }
+
+  fn visit_i128<E: de::Error>(self, n: i128) -> Result<Answer, E> {
+    Ok(Answer::from(n))
+  }
+
+  fn visit_u128<E: de::Error>(self, n: u128) -> Result<Answer, E> {
+    Ok(Answer::from(n))
+  }
+
+  fn visit_str<E: de::Error>(self, s: &str) -> Result<Answer, E> {
+    Ok(Answer::from(s))
+  }
+
+  fn visit_string<E: de::Error>(self, s: String) -> Result<Answer, E> {
+    Ok(Answer::from(s))
+  }
+}
+
+impl<'de> serde::Deserialize<'de> for Answer {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    deserializer.deserialize_any(AnswerVisitor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Answer;
+
+  #[test]
+  fn test_normalize() {
+    assert_eq!(Answer::Int(7), Answer::from(7u32));
+    assert_eq!(Answer::from(7usize), Answer::from(7i64));
+    assert_eq!(Answer::BigInt(1 << 70), Answer::from(1i128 << 70));
+    assert_eq!(Answer::Text(u128::MAX.to_string()), Answer::from(u128::MAX));
+    assert_eq!(Answer::Text("4,6,3".to_string()), Answer::from("4,6,3"));
+    assert_eq!(Answer::Multiline("#.\n.#".to_string()), Answer::from("#. \n.#\n\n".to_string()));
+    assert_eq!(Answer::Int(-3), Answer::parse("-3"));
+    assert_eq!(Answer::Text("6,1".to_string()), Answer::parse("6,1"));
+    assert_ne!(Answer::Int(12), Answer::from("12"));
+    assert_eq!("12 and \"12\"", format!("{} and {}", Answer::Int(12).literal(),
+                                        Answer::from("12").literal()));
+  }
+
+  #[test]
+  fn test_yaml() {
+    let answers = vec![Some(Answer::Int(12)), None, Some(Answer::from("12")),
+                       Some(Answer::BigInt(1 << 70)), Some(Answer::from("a\nb"))];
+    let yaml = serde_yaml::to_string(&answers).unwrap();
+    assert!(yaml.starts_with("- 12\n- null\n- '12'\n- 1180591620717411303424\n"), "{yaml}");
+    assert_eq!(answers, serde_yaml::from_str::<Vec<Option<Answer>>>(&yaml).unwrap());
+  }
+
+  #[test]
+  fn test_json() {
+    assert_eq!("[12,\"4,6,3\"]",
+               serde_json::to_string(&[Answer::Int(12), Answer::from("4,6,3")]).unwrap());
+  }
+}
@@ -0,0 +1,168 @@
+use aoc2024::day::Day;
+use aoc2024::day17::{self, asm, disasm, repl, solve, trace};
+
+use argh::FromArgs;
+use std::fs;
+use std::io::{self, Read};
+
+#[derive(FromArgs)]
+/** Tools for reading the programs of the day 17 computer */
+struct Args {
+    #[argh(subcommand)]
+    command: Command,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Command {
+    Asm(Asm),
+    Disasm(Disasm),
+    Decompile(Decompile),
+    Debug(Debug),
+    Solve(Solve),
+    Trace(Trace),
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "asm")]
+/// assemble mnemonics into a program, or a whole puzzle input when the
+/// registers are set with lines such as a = 729
+struct Asm {
+    /// the assembly (defaults to stdin)
+    #[argh(positional)]
+    file: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "disasm")]
+/// list the program's instructions with their operands and jump targets
+struct Disasm {
+    /// the puzzle input (defaults to input/day17.txt)
+    #[argh(positional, default = "String::from(\"input/day17.txt\")")]
+    file: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "decompile")]
+/// write the program as pseudo-code, if it's a single loop
+struct Decompile {
+    /// the puzzle input (defaults to input/day17.txt)
+    #[argh(positional, default = "String::from(\"input/day17.txt\")")]
+    file: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "debug")]
+/// step through the program with commands read from stdin, see help
+struct Debug {
+    /// the puzzle input (defaults to input/day17.txt)
+    #[argh(positional, default = "String::from(\"input/day17.txt\")")]
+    file: String,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "solve")]
+/// find the smallest A that makes the program output a sequence
+struct Solve {
+    /// the puzzle input (defaults to input/day17.txt)
+    #[argh(positional, default = "String::from(\"input/day17.txt\")")]
+    file: String,
+    /// the output to look for, such as 2,4,1 (defaults to the program itself)
+    #[argh(option)]
+    output: Option<String>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand, name = "trace")]
+/// run the program and list every instruction it executes, then how many
+/// there were and what each loop iteration output
+struct Trace {
+    /// the puzzle input (defaults to input/day17.txt)
+    #[argh(positional, default = "String::from(\"input/day17.txt\")")]
+    file: String,
+    /// run with register A set to this instead of the input's value
+    #[argh(option)]
+    a: Option<u64>,
+    /// write the steps as json lines instead of a table, with the summary
+    /// on stderr
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Read a file, exiting with the reason if that fails.
+fn read_text(file: &str) -> String {
+    fs::read_to_string(file).unwrap_or_else(|e| {
+        eprintln!("Can't read {file}: {e}");
+        std::process::exit(1);
+    })
+}
+
+/// Read and parse a puzzle input, exiting with the reason if that fails.
+fn read(file: &str) -> day17::Debugger {
+    day17::Solution::parse(&read_text(file)).unwrap_or_else(|e| {
+        eprintln!("{file}: {e}");
+        std::process::exit(1);
+    })
+}
+
+fn main() {
+    let args: Args = argh::from_env();
+    match args.command {
+        Command::Asm(c) => {
+            let text = match &c.file {
+                Some(file) => read_text(file),
+                None => {
+                    let mut text = String::new();
+                    io::stdin().read_to_string(&mut text).expect("Can't read stdin");
+                    text
+                }
+            };
+            match asm::assemble(&text) {
+                Ok(program) => print!("{}", program.to_input()),
+                Err(e) => {
+                    eprintln!("{}: {e}", c.file.as_deref().unwrap_or("stdin"));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Disasm(c) => print!("{}", disasm::disassemble(&read(&c.file))),
+        Command::Decompile(c) => match disasm::decompile(&read(&c.file)) {
+            Some(code) => print!("{code}"),
+            None => {
+                eprintln!("The program isn't a single loop, see disasm for its listing");
+                std::process::exit(1);
+            }
+        },
+        Command::Debug(c) => {
+            repl::debug(&read(&c.file), io::stdin().lock(), &mut io::stdout())
+              .expect("Can't talk to the terminal");
+        }
+        Command::Solve(c) => {
+            let d = read(&c.file);
+            let target: Vec<u64> = match &c.output {
+                Some(output) => output.split(',').map(|v| v.trim().parse()).collect::<Result<_, _>>()
+                    .unwrap_or_else(|e| {
+                        eprintln!("Invalid output '{output}': {e}");
+                        std::process::exit(2);
+                    }),
+                None => d.to_instructions(),
+            };
+            match solve::solve(&d, &target) {
+                Some(a) => println!("{a}"),
+                None => {
+                    eprintln!("No value of A was found that outputs {target:?}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Trace(c) => {
+            let trace = trace::trace(&read(&c.file), c.a);
+            if c.json {
+                print!("{}", trace.to_json_lines());
+                eprint!("{}", trace.summary());
+            } else {
+                print!("{}\n{}", trace.to_table(), trace.summary());
+            }
+        }
+    }
+}
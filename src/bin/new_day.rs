@@ -0,0 +1,28 @@
+use aoc2024::scaffold;
+
+use argh::FromArgs;
+use std::path::Path;
+
+#[derive(FromArgs)]
+/** Add a new day: create src/dayN.rs from a template, register it in the
+day list and the benchmarks, and create an empty input/dayN.txt */
+struct Args {
+    /// the day to add
+    #[argh(positional)]
+    day: u32,
+}
+
+fn main() {
+    let args: Args = argh::from_env();
+    match scaffold::new_day(Path::new("."), args.day) {
+        Ok(written) => {
+            for file in written {
+                println!("Wrote {file}");
+            }
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
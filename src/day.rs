@@ -0,0 +1,207 @@
+use crate::answer::Answer;
+use crate::params::{Param, Params};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The code for one day's puzzle. The generator parses the input once and
+/// both parts then work from the parsed form.
+pub trait Day {
+  type Input;
+  type Output1: Into<Answer> + PartialEq;
+  type Output2: Into<Answer> + PartialEq;
+
+  /// The examples from the puzzle text, with their answers.
+  const EXAMPLES: &'static [Example] = &[];
+
+  /// The names, default values and valid ranges of the day's parameters,
+  /// such as the size of a grid or the number of rounds, which differ in
+  /// the examples.
+  const PARAMS: &'static [Param] = &[];
+
+  fn generator(input: &str) -> Result<Self::Input, ParseError>;
+  fn part1(input: &Self::Input) -> Self::Output1;
+  fn part2(input: &Self::Input) -> Self::Output2;
+
+  /// The generator for a day whose parameters change how the input is
+  /// parsed. The params hold a value for each of the day's PARAMS.
+  fn generator_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+    Self::generator(input)
+  }
+
+  /// Part 1 for a day whose parameters change the answer.
+  fn part1_with(input: &Self::Input, _params: &Params) -> Self::Output1 {
+    Self::part1(input)
+  }
+
+  /// Part 2 for a day whose parameters change the answer.
+  fn part2_with(input: &Self::Input, _params: &Params) -> Self::Output2 {
+    Self::part2(input)
+  }
+
+  /// The default parameters with the given ones on top.
+  fn params(overrides: &Params) -> Params {
+    Params::from(Self::PARAMS).with(overrides)
+  }
+
+  /// Run the generator and fill in the line and column of any error.
+  fn parse(input: &str) -> Result<Self::Input, ParseError> {
+    Self::parse_with(input, &Self::params(&Params::default()))
+  }
+
+  /// Like parse, but with the given parameters.
+  fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+    Self::generator_with(input, params).map_err(|e| e.locate(input))
+  }
+}
+
+/// An example input from a puzzle's text.
+#[derive(Clone, Copy, Debug)]
+pub struct Example {
+  pub input: &'static str,
+  /// The answers of the two parts, or None for a part that the example
+  /// doesn't apply to
+  pub answers: [Option<&'static str>; 2],
+  /// The parameters that this example needs, such as a smaller grid
+  pub params: &'static [(&'static str, i64)],
+}
+
+impl Example {
+  /// The answers in the form that DayResult::check expects, where those
+  /// that read as numbers are numbers.
+  pub fn expected(&self) -> Vec<Option<Answer>> {
+    self.answers.iter().map(|a| a.map(Answer::parse)).collect()
+  }
+}
+
+/// What went wrong while parsing the input.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseErrorKind {
+  /// A separator, section, or field that the format requires wasn't there
+  Missing(String),
+  /// The text couldn't be converted into a value
+  InvalidValue { text: String, reason: String },
+  /// A character that isn't allowed at this position
+  UnexpectedChar(char),
+  /// The input is well formed, but doesn't describe a valid puzzle
+  Invalid(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      ParseErrorKind::Missing(what) => write!(f, "missing {what}"),
+      ParseErrorKind::InvalidValue { text, reason } => write!(f, "invalid value {text:?}: {reason}"),
+      ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {c:?}"),
+      ParseErrorKind::Invalid(why) => write!(f, "{why}"),
+    }
+  }
+}
+
+/// An error in a day's input. The line and column are 1-based and are
+/// filled in by Day::parse, which knows the whole input. Before that, or
+/// when the text isn't part of the input, they are zero.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+  pub kind: ParseErrorKind,
+  pub line: usize,
+  pub column: usize,
+  // The address of the offending text, which is only compared and never read
+  address: usize,
+}
+
+impl ParseError {
+  /// Create an error for the given text, which should be a slice of the input.
+  pub fn at(text: &str, kind: ParseErrorKind) -> Self {
+    ParseError { kind, line: 0, column: 0, address: text.as_ptr() as usize }
+  }
+
+  pub fn missing(text: &str, what: &str) -> Self {
+    Self::at(text, ParseErrorKind::Missing(what.to_string()))
+  }
+
+  pub fn invalid(text: &str, why: &str) -> Self {
+    Self::at(text, ParseErrorKind::Invalid(why.to_string()))
+  }
+
+  /// Create an error for the unexpected character at the start of text.
+  pub fn unexpected(text: &str) -> Self {
+    Self::at(text, ParseErrorKind::UnexpectedChar(text.chars().next().unwrap_or('\0')))
+  }
+
+  /// Work out the line and column of the error within the input.
+  pub fn locate(mut self, input: &str) -> Self {
+    let start = input.as_ptr() as usize;
+    if self.line == 0 && self.address >= start && self.address <= start + input.len() {
+      let before = &input[..self.address - start];
+      self.line = before.matches('\n').count() + 1;
+      self.column = before.rsplit('\n').next().unwrap().chars().count() + 1;
+    }
+    self
+  }
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.line == 0 {
+      write!(f, "{}", self.kind)
+    } else {
+      write!(f, "line {} column {}: {}", self.line, self.column, self.kind)
+    }
+  }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse the text into a value, with the text's position on failure.
+pub fn parse<T>(text: &str) -> Result<T, ParseError> where T: FromStr, T::Err: fmt::Display {
+  text.parse().map_err(|e: T::Err| ParseError::at(text, ParseErrorKind::InvalidValue {
+    text: text.to_string(),
+    reason: e.to_string(),
+  }))
+}
+
+/// Split the text at the first occurrence of the separator.
+pub fn split_once<'a>(text: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseError> {
+  text.split_once(separator)
+    .ok_or_else(|| ParseError::missing(text, &format!("{separator:?}")))
+}
+
+/// Split the text at the last occurrence of the separator.
+pub fn rsplit_once<'a>(text: &'a str, separator: &str) -> Result<(&'a str, &'a str), ParseError> {
+  text.rsplit_once(separator)
+    .ok_or_else(|| ParseError::missing(text, &format!("{separator:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse, split_once, ParseError, ParseErrorKind};
+
+  const INPUT: &str = "1,2\n3,x\n\n5;6";
+
+  #[test]
+  fn test_locate() {
+    let line = INPUT.lines().nth(1).unwrap();
+    let err = parse::<u32>(split_once(line, ",").unwrap().1).unwrap_err().locate(INPUT);
+    assert_eq!((2, 3), (err.line, err.column));
+    assert_eq!(ParseErrorKind::InvalidValue {
+      text: "x".to_string(),
+      reason: "invalid digit found in string".to_string(),
+    }, err.kind);
+    assert_eq!("line 2 column 3: invalid value \"x\": invalid digit found in string",
+               err.to_string());
+
+    let line = INPUT.lines().nth(3).unwrap();
+    let err = split_once(line, ",").unwrap_err().locate(INPUT);
+    assert_eq!((4, 1), (err.line, err.column));
+    assert_eq!("line 4 column 1: missing \",\"", err.to_string());
+  }
+
+  #[test]
+  fn test_not_in_input() {
+    let other = String::from("abc");
+    let err = ParseError::unexpected(&other).locate(INPUT);
+    assert_eq!((0, 0), (err.line, err.column));
+    assert_eq!("unexpected character 'a'", err.to_string());
+  }
+}
@@ -1,5 +1,37 @@
+use crate::day::{self, Day, Example, ParseError};
 use std::iter::Iterator;
 
+const EXAMPLE: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = (Vec<u32>, Vec<u32>);
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("11"), Some("31")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
+
 pub fn part1(locations: &(Vec<u32>, Vec<u32>)) -> u32 {
     let (l_locs, r_locs) = locations;
     l_locs.iter().zip(r_locs.iter()).map(|(l, r)| l.abs_diff(*r)).sum()
@@ -31,35 +63,29 @@ pub fn part2(locations: &(Vec<u32>, Vec<u32>)) -> u32 {
     similarity_score
 }
 
-pub fn generator(input: &str) -> (Vec<u32>, Vec<u32>) {
+pub fn generator(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
     let mut left_locations: Vec<u32> = Vec::new();
     let mut right_locations: Vec<u32> = Vec::new();
 
     for line in input.lines() {
-        let (l, r) = line.split_once("   ").unwrap();
-        left_locations.push(l.parse::<u32>().unwrap());
-        right_locations.push(r.parse::<u32>().unwrap());
+        let (l, r) = day::split_once(line, "   ")?;
+        left_locations.push(day::parse(l)?);
+        right_locations.push(day::parse(r)?);
     }
     left_locations.sort();
     right_locations.sort();
 
-    (left_locations, right_locations)
+    Ok((left_locations, right_locations))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "3   4
-4   3
-2   5
-1   3
-3   9
-3   3";
+    use super::{generator, part1, part2, Solution, EXAMPLE};
+    use crate::day::Day;
 
     #[test]
     fn test_generator() {
-        let (l, r) = generator(&INPUT);
+        let (l, r) = generator(EXAMPLE).unwrap();
         assert_eq!(l.len(), 6);
         assert_eq!(r.len(), 6);
         assert_eq!(l, vec![1, 2, 3, 3, 3, 4]);
@@ -68,13 +94,21 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let values = generator(&INPUT);
+        let values = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&values), 11);
     }
 
     #[test]
     fn test_part_2() {
-        let values = generator(&INPUT);
+        let values = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&values), 31);
     }
+
+    #[test]
+    fn test_invalid_input() {
+        let err = Solution::parse("3   4\n4   x3").unwrap_err();
+        assert_eq!((2, 5), (err.line, err.column));
+        let err = Solution::parse("3   4\n4 3").unwrap_err();
+        assert_eq!((2, 1), (err.line, err.column));
+    }
 }
@@ -1,6 +1,38 @@
-use std::iter::Iterator;
+use crate::day::{Day, Example, ParseError};
+use crate::utils::grid::{Grid, Point};
 
-const NEIGHBORS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const EXAMPLE: &str = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Puzzle;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("36"), Some("81")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
 
 pub fn part1(p: &Puzzle) -> u32 {
     p.score_all(true)
@@ -10,112 +42,69 @@ pub fn part2(p: &Puzzle) -> u32 {
     p.score_all(false)
 }
 
-pub fn generator(input: &str) -> Puzzle {
+pub fn generator(input: &str) -> Result<Puzzle, ParseError> {
     Puzzle::new(input)
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Copy, Clone)]
-struct XY {
-    x: i32,
-    y: i32,
-}
-
 pub struct Puzzle {
-    map: Vec<Vec<u8>>,
-    width: i32,
-    height: i32,
+    map: Grid<u8>,
 }
 
 impl Puzzle {
-    fn new(input: &str) -> Self {
-        let map: Vec<Vec<u8>> = input.lines()
-            .map(|l| { l.chars().map(|c| c.to_digit(10).unwrap() as u8).collect() })
-            .collect();
-        let height = map.len() as i32;
-        let width = map[0].len() as i32;
-        Puzzle { map, width, height }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::parse(input, |c| c.to_digit(10).map(|d| d as u8))?;
+        Ok(Puzzle { map })
     }
 
     fn score_all(&self, unique: bool) -> u32 {
-        self.map.iter().enumerate().flat_map(|(y, row)| {
-            row.iter().enumerate().filter_map(move |(x, pixel)| {
-                if pixel == &0 {
-                    return Some(self.score(XY { x: x as i32, y: y as i32 }, unique));
-                } else {
-                    None
-                }
-            })
-        }).sum()
+        self.map.iter()
+            .filter(|(_, height)| **height == 0)
+            .map(|(p, _)| self.score(p, unique))
+            .sum()
     }
 
-    fn score(&self, p: XY, unique: bool) -> u32 {
-        let mut stack = Vec::new();
-        stack.push(XY { x: p.x, y: p.y });
+    fn score(&self, p: Point, unique: bool) -> u32 {
+        let mut stack = vec![p];
         let mut visited = Vec::new();
 
-        while !stack.is_empty() {
-            let c = stack.pop().unwrap();
-            let ch = self.height(&c).unwrap();
+        while let Some(c) = stack.pop() {
+            let ch = self.map[c];
             if ch == 9 {
                 visited.push(c);
                 continue;
             }
 
-            NEIGHBORS.iter().for_each(|(dx, dy)| {
-                let p = XY { x: c.x + dx, y: c.y + dy };
-                match self.height(&p) {
-                    Some(h) if ch + 1 == h => stack.push(p),
-                    _ => {}
-                }
-            });
+            stack.extend(self.map.neighbors(c).filter(|n| self.map[*n] == ch + 1));
         }
 
         if unique {
-            visited.sort_by_key(|xy| (xy.x, xy.y));
+            visited.sort();
             visited.dedup();
         }
         visited.len() as u32
     }
-
-    fn height(&self, p: &XY) -> Option<u8> {
-        if p.x > -1 && p.x < self.width
-            && p.y > -1 && p.y < self.height {
-            Some(self.map[p.y as usize][p.x as usize])
-        } else {
-            None
-        }
-    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let p = generator(&INPUT);
-        assert_eq!(p.height, 8);
-        assert_eq!(p.width, 8);
+        let p = generator(EXAMPLE).unwrap();
+        assert_eq!(p.map.height(), 8);
+        assert_eq!(p.map.width(), 8);
     }
 
     #[test]
     fn test_part_1() {
-        let p = generator(&INPUT);
+        let p = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&p), 36);
     }
 
     #[test]
     fn test_part_2() {
-        let p = generator(&INPUT);
+        let p = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&p), 81);
     }
 }
@@ -1,16 +1,62 @@
+use crate::day::{self, Day, Example, ParseError};
+use crate::params::{Param, Params};
 use std::collections::HashMap;
 
+const BLINKS_1: u32 = 25;
+const BLINKS_2: u32 = 75;
+// More blinks than this and the number of stones doesn't fit in a u64
+const MAX_BLINKS: i64 = 90;
+
+const EXAMPLE: &str = "125 17";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Vec<u64>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("55312"), Some("65601038650482")], params: &[] },
+    ];
+
+    const PARAMS: &'static [Param] = &[
+        ("blinks1", BLINKS_1 as i64, 0..=MAX_BLINKS),
+        ("blinks2", BLINKS_2 as i64, 0..=MAX_BLINKS),
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part1_with(input: &Self::Input, params: &Params) -> u64 {
+        blink(input, params.get("blinks1") as u32)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+
+    fn part2_with(input: &Self::Input, params: &Params) -> u64 {
+        blink(input, params.get("blinks2") as u32)
+    }
+}
+
 pub fn part1(stones: &Vec<u64>) -> u64 {
-    blink(stones, 25)
+    blink(stones, BLINKS_1)
 }
 
 pub fn part2(stones: &Vec<u64>) -> u64 {
-    blink(stones, 75)
+    blink(stones, BLINKS_2)
 }
 
-pub fn generator(input: &str) -> Vec<u64> {
+pub fn generator(input: &str) -> Result<Vec<u64>, ParseError> {
     input.split_whitespace()
-        .map(|x| x.parse().unwrap())
+        .map(day::parse)
         .collect()
 }
 
@@ -50,25 +96,23 @@ fn from_cache_or_compute(n: u64, times: u32, cache: &mut Vec<HashMap<u64, u64>>)
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "125 17";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let stones = generator(&INPUT);
+        let stones = generator(EXAMPLE).unwrap();
         assert_eq!(2, stones.len());
     }
 
     #[test]
     fn test_part_1() {
-        let stones = generator(&INPUT);
+        let stones = generator(EXAMPLE).unwrap();
         assert_eq!(55312, part1(&stones));
     }
 
     #[test]
     fn test_part_2() {
-        let stones = generator(&INPUT);
+        let stones = generator(EXAMPLE).unwrap();
         assert_eq!(65601038650482, part2(&stones));
     }
 }
@@ -1,6 +1,36 @@
-use crate::day12::Direction::{EAST, NORTH, SOUTH, WEST};
+use crate::day::{Day, Example, ParseError};
+use crate::utils::grid::{Grid, Point};
 use std::collections::{HashMap, HashSet};
 
+const EXAMPLE: &str = "AAAA
+BBCD
+BBCC
+EEEC";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Garden;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("140"), Some("80")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
+
 pub fn part1(garden: &Garden) -> u32 {
     garden.fence_price(false)
 }
@@ -9,13 +39,10 @@ pub fn part2(garden: &Garden) -> u32 {
     garden.fence_price(true)
 }
 
-pub fn generator(input: &str) -> Garden {
-    let arrangement: Vec<Vec<char>> = input.lines()
-        .map(|line| line.chars().collect())
-        .collect();
+pub fn generator(input: &str) -> Result<Garden, ParseError> {
+    let arrangement = Grid::parse(input, Some)?;
 
-    let directions = [NORTH, SOUTH, WEST, EAST];
-    let mut region_lots = compute_regions(&arrangement, &directions);
+    let mut region_lots = compute_regions(&arrangement);
     let mut regions = Vec::new();
     while !region_lots.is_empty() {
         let plots = region_lots.pop().unwrap();
@@ -23,16 +50,16 @@ pub fn generator(input: &str) -> Garden {
         regions.push(Region { plots, edges });
     }
 
-    Garden {
+    Ok(Garden {
         regions
-    }
+    })
 }
 
-fn compute_region_edges(region: &HashSet<XY>) -> HashSet<Edge> {
+fn compute_region_edges(region: &HashSet<Point>) -> HashSet<Edge> {
     let mut edges: HashMap<Edge, u32> = HashMap::new();
     region.iter().for_each(|r| {
-        [NORTH, SOUTH, WEST, EAST].iter().for_each(|d| {
-            let edge = Edge::new(&r.x, &r.y, d);
+        r.neighbors().for_each(|n| {
+            let edge = Edge::new(*r, n);
             edges.entry(edge)
                 .and_modify(|c| *c += 1)
                 .or_insert(1);
@@ -42,46 +69,33 @@ fn compute_region_edges(region: &HashSet<XY>) -> HashSet<Edge> {
     edges.into_keys().collect()
 }
 
-fn compute_regions(arrangement: &Vec<Vec<char>>, directions: &[Direction]) -> Vec<HashSet<XY>> {
+fn compute_regions(arrangement: &Grid<char>) -> Vec<HashSet<Point>> {
     let mut regions = Vec::new();
-    let mut allocated = vec![vec![false; arrangement[0].len()]; arrangement.len()];
+    let mut allocated = arrangement.map(|_| false);
     let mut stack = Vec::new();
-    let width: i32 = arrangement[0].len() as i32;
-    let height: i32 = arrangement.len() as i32;
 
-    arrangement.iter().enumerate().for_each(|(y, row)| {
-        row.iter().enumerate().for_each(|(x, _)| {
-            if allocated[y][x] {
-                return;
-            }
-            stack.clear();
-            stack.push(XY { x: x as i32, y: y as i32 });
-            let plant = arrangement[y][x];
-            let mut plots = HashSet::new();
-            while !stack.is_empty() {
-                let p = stack.pop().unwrap();
-                if allocated[p.y as usize][p.x as usize] {
-                    continue;
-                }
-                for d in directions {
-                    let n = d.adjust(&p);
-                    if n.x > -1 && n.x < width
-                        && n.y > -1 && n.y < height
-                        && &arrangement[n.y as usize][n.x as usize] == &plant {
-                        stack.push(n);
-                    }
-                }
-                allocated[p.y as usize][p.x as usize] = true;
-                plots.insert(p);
+    arrangement.iter().for_each(|(start, plant)| {
+        if allocated[start] {
+            return;
+        }
+        stack.clear();
+        stack.push(start);
+        let mut plots = HashSet::new();
+        while let Some(p) = stack.pop() {
+            if allocated[p] {
+                continue;
             }
-            regions.push(plots);
-        })
+            stack.extend(arrangement.neighbors(p).filter(|n| arrangement[*n] == *plant));
+            allocated[p] = true;
+            plots.insert(p);
+        }
+        regions.push(plots);
     });
     regions
 }
 
 pub struct Region {
-    plots: HashSet<XY>,
+    plots: HashSet<Point>,
     edges: HashSet<Edge>,
 }
 
@@ -100,14 +114,14 @@ impl Region {
                 let p = Edge { x1: e.x1 - 1, x2: e.x1 - 1, y1: e.y1, y2: e.y2 };
                 // check that x-1, y1 and x,y1 are in region or x-1, y2 and x, y2 are in region
                 !self.edges.contains(&p)
-                    || self.plots.contains(&XY { x: p.x1, y: e.y1 }) != self.plots.contains(&XY { x: e.x1, y: e.y1 })
-                    || self.plots.contains(&XY { x: p.x1, y: e.y2 }) != self.plots.contains(&XY { x: e.x1, y: e.y2 })
+                    || self.plots.contains(&Point::new(p.x1, e.y1)) != self.plots.contains(&Point::new(e.x1, e.y1))
+                    || self.plots.contains(&Point::new(p.x1, e.y2)) != self.plots.contains(&Point::new(e.x1, e.y2))
             } else {
                 let p = Edge { x1: e.x1, x2: e.x2, y1: e.y1 - 1, y2: e.y1 - 1 };
                 // check that x1, y-1 and x2, y-1 are in region or x1, y and x2, y
                 !self.edges.contains(&p)
-                    || self.plots.contains(&XY { x: e.x1, y: p.y1 }) != self.plots.contains(&XY { x: e.x1, y: e.y1 })
-                    || self.plots.contains(&XY { x: e.x1, y: p.y1 }) != self.plots.contains(&XY { x: e.x1, y: e.y2 })
+                    || self.plots.contains(&Point::new(e.x1, p.y1)) != self.plots.contains(&Point::new(e.x1, e.y1))
+                    || self.plots.contains(&Point::new(e.x1, p.y1)) != self.plots.contains(&Point::new(e.x1, e.y2))
             }
         }).count()
     }
@@ -125,31 +139,6 @@ impl Garden {
     }
 }
 
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
-struct XY {
-    x: i32,
-    y: i32,
-}
-
-#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
-enum Direction {
-    NORTH,
-    SOUTH,
-    EAST,
-    WEST,
-}
-
-impl Direction {
-    fn adjust(&self, xy: &XY) -> XY {
-        match self {
-            NORTH => XY { x: xy.x, y: xy.y - 1 },
-            SOUTH => XY { x: xy.x, y: xy.y + 1 },
-            EAST => XY { x: xy.x + 1, y: xy.y },
-            WEST => XY { x: xy.x - 1, y: xy.y },
-        }
-    }
-}
-
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
 struct Edge {
     x1: i32,
@@ -159,41 +148,32 @@ struct Edge {
 }
 
 impl Edge {
-    fn new(x: &i32, y: &i32, d: &Direction) -> Self {
-        match d {
-            NORTH => Self { x1: *x, x2: *x, y1: y - 1, y2: *y },
-            SOUTH => Self { x1: *x, x2: *x, y1: *y, y2: y + 1 },
-            EAST => Self { x1: *x, x2: x + 1, y1: *y, y2: *y },
-            WEST => Self { x1: x - 1, x2: *x, y1: *y, y2: *y },
-        }
+    /// The edge between two plots that share a side.
+    fn new(p: Point, n: Point) -> Self {
+        Self { x1: p.x.min(n.x), x2: p.x.max(n.x), y1: p.y.min(n.y), y2: p.y.max(n.y) }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "AAAA
-BBCD
-BBCC
-EEEC";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let g = generator(&INPUT);
+        let g = generator(EXAMPLE).unwrap();
         assert_eq!(g.regions.len(), 5);
     }
 
     #[test]
     fn test_part_1() {
-        let g = generator(&INPUT);
+        let g = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&g), 140);
 
         let g = generator("OOOOO
 OXOXO
 OOOOO
 OXOXO
-OOOOO");
+OOOOO").unwrap();
         assert_eq!(part1(&g), 772);
 
         let g = generator("RRRRIICCFF
@@ -205,40 +185,40 @@ VVIVCCJJEE
 VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
-MMMISSJEEE");
+MMMISSJEEE").unwrap();
         assert_eq!(part1(&g), 1930);
     }
 
     #[test]
     fn test_part_2() {
-        let g = generator(&INPUT);
+        let g = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&g), 80);
 
         let g = generator("EBBA
 EBBA
 ECCA
-CCDA");
+CCDA").unwrap();
         assert_eq!(part2(&g), 80);
 
         let g = generator("OOOOO
 OXOXO
 OOOOO
 OXOXO
-OOOOO");
+OOOOO").unwrap();
         assert_eq!(part2(&g), 436);
 
         let g = generator("EEEEE
 EXXXX
 EEEEE
 EXXXX
-EEEEE");
+EEEEE").unwrap();
         assert_eq!(part2(&g), 236);
 
         let g = generator("EEEEE
 EXXXX
 EEEEE
 EXXXX
-EEEEE");
+EEEEE").unwrap();
         assert_eq!(part2(&g), 236);
 
         let g = generator("RRRRIICCFF
@@ -250,7 +230,7 @@ VVIVCCJJEE
 VVIIICJJEE
 MIIIIIJJEE
 MIIISIJEEE
-MMMISSJEEE");
+MMMISSJEEE").unwrap();
         assert_eq!(part2(&g), 1206);
     }
 }
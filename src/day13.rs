@@ -1,26 +1,77 @@
+use crate::day::{self, Day, Example, ParseError};
+use crate::params::{Param, Params};
 const A_TOKENS: i64 = 3;
 const B_TOKENS: i64 = 1;
+const OFFSET: i64 = 10000000000000;
+// A larger offset overflows when solving for the presses
+const MAX_OFFSET: i64 = 1000000000000000;
+
+const EXAMPLE: &str = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Vec<Machine>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("480"), Some("875318608908")], params: &[] },
+    ];
+
+    const PARAMS: &'static [Param] = &[("offset", OFFSET, 0..=MAX_OFFSET)];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> i64 {
+        part2(input)
+    }
+
+    fn part2_with(input: &Self::Input, params: &Params) -> i64 {
+        solve(input, params.get("offset"))
+    }
+}
 
 pub fn part1(machines: &Vec<Machine>) -> i64 {
-    solve(machines, false)
+    solve(machines, 0)
 }
 
 pub fn part2(machines: &Vec<Machine>) -> i64 {
-    solve(machines, true)
+    solve(machines, OFFSET)
 }
 
-fn solve(machines: &Vec<Machine>, correct_error: bool) -> i64 {
+fn solve(machines: &Vec<Machine>, offset: i64) -> i64 {
     let mut a = 0;
     let mut b = 0;
     machines.iter().for_each(|m| {
-        let (a_times, b_times) = m.solve(correct_error);
+        let (a_times, b_times) = m.solve(offset);
         a += a_times;
         b += b_times;
     });
     (a * A_TOKENS) + (b * B_TOKENS)
 }
 
-pub fn generator(input: &str) -> Vec<Machine> {
+pub fn generator(input: &str) -> Result<Vec<Machine>, ParseError> {
     input.split("\n\n")
         .map(|chunk| Machine::new(chunk))
         .collect()
@@ -33,15 +84,15 @@ struct XY {
 }
 
 impl XY {
-    fn new(input: &str, sep: &str) -> XY {
-        let xy = input.split_once(", ").unwrap();
-        let x = xy.0.split_once(sep).unwrap().1.parse::<i64>().unwrap();
-        let y = xy.1.split_once(sep).unwrap().1.parse::<i64>().unwrap();
+    fn new(input: &str, sep: &str) -> Result<XY, ParseError> {
+        let xy = day::split_once(input, ", ")?;
+        let x = day::parse::<i64>(day::split_once(xy.0, sep)?.1)?;
+        let y = day::parse::<i64>(day::split_once(xy.1, sep)?.1)?;
 
-        Self {
+        Ok(Self {
             x,
             y,
-        }
+        })
     }
 }
 
@@ -52,22 +103,26 @@ pub struct Machine {
 }
 
 impl Machine {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut lines = input.lines();
-        let a = XY::new(lines.next().unwrap().split_once(": ").unwrap().1, "+");
-        let b = XY::new(lines.next().unwrap().split_once(": ").unwrap().1, "+");
-        let prize = XY::new(lines.next().unwrap().split_once(": ").unwrap().1, "=");
-
-        Machine {
+        let mut next = |what: &str, sep: &str| {
+            let line = lines.next().ok_or_else(|| ParseError::missing(&input[input.len()..], what))?;
+            XY::new(day::split_once(line, ": ")?.1, sep)
+        };
+        let a = next("button A", "+")?;
+        let b = next("button B", "+")?;
+        let prize = next("prize", "=")?;
+
+        Ok(Machine {
             a,
             b,
             prize,
-        }
+        })
     }
 
-    fn solve(&self, correct_error: bool) -> (i64, i64) {
-        let px = if correct_error { 10000000000000 + self.prize.x } else { self.prize.x };
-        let py = if correct_error { 10000000000000 + self.prize.y } else { self.prize.y };
+    fn solve(&self, offset: i64) -> (i64, i64) {
+        let px = offset + self.prize.x;
+        let py = offset + self.prize.y;
         let n = (px * self.b.y) - (self.b.x * py);
         let d = (self.a.x * self.b.y) - (self.b.x * self.a.y);
         if n % d != 0 { return (0, 0); }
@@ -89,27 +144,12 @@ impl Machine {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2, XY};
-
-    const INPUT: &str = "Button A: X+94, Y+34
-Button B: X+22, Y+67
-Prize: X=8400, Y=5400
-
-Button A: X+26, Y+66
-Button B: X+67, Y+21
-Prize: X=12748, Y=12176
-
-Button A: X+17, Y+86
-Button B: X+84, Y+37
-Prize: X=7870, Y=6450
-
-Button A: X+69, Y+23
-Button B: X+27, Y+71
-Prize: X=18641, Y=10279";
+    use super::{generator, part1, part2, Solution, XY, EXAMPLE};
+    use crate::day::Day;
 
     #[test]
     fn test_generator() {
-        let machines = generator(&INPUT);
+        let machines = generator(EXAMPLE).unwrap();
         assert_eq!(4, machines.len());
         assert_eq!(XY { x: 94, y: 34 }, machines[0].a);
         assert_eq!(XY { x: 22, y: 67 }, machines[0].b);
@@ -118,13 +158,22 @@ Prize: X=18641, Y=10279";
 
     #[test]
     fn test_part_1() {
-        let machine = generator(&INPUT);
+        let machine = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&machine), 480);
     }
 
     #[test]
     fn test_part_2() {
-        let machine = generator(&INPUT);
+        let machine = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&machine), 875318608908);
     }
+
+    #[test]
+    fn test_invalid_input() {
+        let err = Solution::parse(&EXAMPLE.replace("Y=6450", "Y=645O")).err().unwrap();
+        assert_eq!((11, 18), (err.line, err.column));
+        let err = Solution::parse(&EXAMPLE.replace("\nPrize: X=12748, Y=12176", "")).err().unwrap();
+        assert_eq!((6, 21), (err.line, err.column));
+        assert_eq!("line 6 column 21: missing prize", err.to_string());
+    }
 }
@@ -1,7 +1,66 @@
+use crate::day::{self, Day, Example, ParseError};
+use crate::params::{Param, Params};
+use crate::utils::grid::{Grid, Point};
 const SAFETY_FACTOR_TIME: u32 = 100;
 const HEIGHT: i32 = 103;
 const WIDTH: i32 = 101;
 const SEARCH_MAX_TIME: u32 = 10000;
+const MAX_SIZE: i64 = 1000;
+// The time is passed around as a u32
+const MAX_SECONDS: i64 = u32::MAX as i64;
+
+const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = EBHQ;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("12"), None],
+                  params: &[("height", 7), ("width", 11)] },
+    ];
+
+    const PARAMS: &'static [Param] = &[
+        ("height", HEIGHT as i64, 1..=MAX_SIZE),
+        ("width", WIDTH as i64, 1..=MAX_SIZE),
+        ("seconds", SAFETY_FACTOR_TIME as i64, 0..=MAX_SECONDS),
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn generator_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        EBHQ::new(input, params.get("height") as i32, params.get("width") as i32)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part1_with(input: &Self::Input, params: &Params) -> u32 {
+        let mut ebhq = input.clone();
+        ebhq.safety_factor(params.get("seconds") as u32)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
 
 pub fn part1(ebhq: &EBHQ) -> u32 {
     let mut ebhq = ebhq.clone();
@@ -20,46 +79,32 @@ pub fn part2(ebhq: &EBHQ) -> u32 {
     panic!("No solution found!");
 }
 
-pub fn generator(input: &str) -> EBHQ {
+pub fn generator(input: &str) -> Result<EBHQ, ParseError> {
     EBHQ::new(input, HEIGHT, WIDTH)
 }
 
-#[derive(Debug, Eq, PartialEq, Clone)]
-struct XY {
-    x: i32,
-    y: i32,
-}
-
-impl XY {
-    fn new(input: &str) -> Self {
-        let (x, y) = input.split_once(",").unwrap();
-        XY {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        }
-    }
-}
-
 #[derive(Debug, Clone)]
 struct Robot {
-    pos: XY,
-    velocity: XY,
+    pos: Point,
+    velocity: Point,
 }
 
 impl Robot {
-    fn new(input: &str) -> Self {
-        let (p, v) = input.split_once(" ").unwrap();
-        Robot {
-            pos: XY::new(p.split_once("=").unwrap().1),
-            velocity: XY::new(v.split_once("=").unwrap().1),
-        }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (p, v) = day::split_once(input, " ")?;
+        Ok(Robot {
+            pos: Point::parse(day::split_once(p, "=")?.1)?,
+            velocity: Point::parse(day::split_once(v, "=")?.1)?,
+        })
     }
 
     fn navigate(&mut self, time: u32, height: &i32, width: &i32) {
-        self.pos.x += self.velocity.x * time as i32;
-        self.pos.y += self.velocity.y * time as i32;
-        self.pos.x = self.pos.x.rem_euclid(*width);
-        self.pos.y = self.pos.y.rem_euclid(*height);
+        // In i64, as the velocity times the time can be far beyond an i32
+        let wrap = |pos: i32, velocity: i32, size: i32| {
+            (pos as i64 + velocity as i64 * time as i64).rem_euclid(size as i64) as i32
+        };
+        self.pos.x = wrap(self.pos.x, self.velocity.x, *width);
+        self.pos.y = wrap(self.pos.y, self.velocity.y, *height);
     }
 
     fn quadrant(&self, mid_height: &i32, mid_width: &i32) -> Option<usize> {
@@ -81,27 +126,36 @@ pub struct EBHQ {
     height: i32,
     width: i32,
     robots: Vec<Robot>,
-    map: Vec<Vec<u32>>,
+    map: Grid<u32>,
 }
 
 impl EBHQ {
-    fn new(input: &str, height: i32, width: i32) -> Self {
+    fn new(input: &str, height: i32, width: i32) -> Result<Self, ParseError> {
         if height % 2 == 0 || width % 2 == 0 {
-            panic!("Invalid height/width: {height}/{width}, should not be even!!!");
+            let why = format!("the height and width must be odd, not {height} and {width}");
+            return Err(ParseError::invalid("", &why));
         }
 
-        let mut map = vec![vec![0; width as usize]; height as usize];
-        let robots: Vec<Robot> = input.lines().map(|line| Robot::new(line)).collect();
+        let mut map = Grid::new(width as usize, height as usize, 0);
+        let robots: Vec<Robot> = input.lines()
+            .map(|line| {
+                let robot = Robot::new(line)?;
+                if !map.contains(robot.pos) {
+                    return Err(ParseError::invalid(line, "robot is outside of the area"));
+                }
+                Ok(robot)
+            })
+            .collect::<Result<_, _>>()?;
         robots.iter().for_each(|robot| {
-            map[robot.pos.y as usize][robot.pos.x as usize] += 1;
+            map[robot.pos] += 1;
         });
 
-        EBHQ {
+        Ok(EBHQ {
             height,
             width,
             robots,
             map,
-        }
+        })
     }
 
 
@@ -123,9 +177,9 @@ impl EBHQ {
 
     fn elapse_time(&mut self, time: u32) {
         self.robots.iter_mut().for_each(|r| {
-            self.map[r.pos.y as usize][r.pos.x as usize] -= 1;
+            self.map[r.pos] -= 1;
             r.navigate(time, &self.height, &self.width);
-            self.map[r.pos.y as usize][r.pos.x as usize] += 1;
+            self.map[r.pos] += 1;
         })
     }
 
@@ -134,7 +188,7 @@ impl EBHQ {
             (2..self.width - 2).any(|x| {
                 (0..3).all(|dy| {
                     (-dy..=dy).all(|dx| {
-                        self.map[(y + dy) as usize][(x + dx) as usize] > 0
+                        self.map[Point::new(x + dx, y + dy)] > 0
                     })
                 })
             })
@@ -144,54 +198,48 @@ impl EBHQ {
 
 #[cfg(test)]
 mod tests {
-    use super::{part1, part2, Robot, EBHQ, XY};
+    use super::{part1, part2, Point, Robot, EBHQ, EXAMPLE};
     const HEIGHT: i32 = 7;
     const WIDTH: i32 = 11;
 
-    const INPUT: &str = "p=0,4 v=3,-3
-p=6,3 v=-1,-3
-p=10,3 v=-1,2
-p=2,0 v=2,-1
-p=0,0 v=1,3
-p=3,0 v=-2,-2
-p=7,6 v=-1,-3
-p=3,0 v=-1,-2
-p=9,3 v=2,3
-p=7,3 v=-1,2
-p=2,4 v=2,-3
-p=9,5 v=-3,-3";
-
     #[test]
     fn test_generator() {
-        let ebhq = EBHQ::new(&INPUT, HEIGHT, WIDTH);
+        let ebhq = EBHQ::new(EXAMPLE, HEIGHT, WIDTH).unwrap();
         assert_eq!(ebhq.robots.len(), 12);
-        assert_eq!(ebhq.robots.first().unwrap().pos, XY { x: 0, y: 4 });
-        assert_eq!(ebhq.robots.first().unwrap().velocity, XY { x: 3, y: -3 });
-        assert_eq!(ebhq.robots.last().unwrap().pos, XY { x: 9, y: 5 });
-        assert_eq!(ebhq.robots.last().unwrap().velocity, XY { x: -3, y: -3 });
+        assert_eq!(ebhq.robots.first().unwrap().pos, Point::new(0, 4));
+        assert_eq!(ebhq.robots.first().unwrap().velocity, Point::new(3, -3));
+        assert_eq!(ebhq.robots.last().unwrap().pos, Point::new(9, 5));
+        assert_eq!(ebhq.robots.last().unwrap().velocity, Point::new(-3, -3));
+        let err = EBHQ::new(EXAMPLE, 4, WIDTH).err().unwrap();
+        assert_eq!(err.to_string(), "the height and width must be odd, not 4 and 11");
     }
 
     #[test]
     fn test_robot() {
-        let r = Robot::new("p=2,4 v=2,-3");
-        assert_eq!(r.pos, XY { x: 2, y: 4 });
-        assert_eq!(r.velocity, XY { x: 2, y: -3 });
+        let r = Robot::new("p=2,4 v=2,-3").unwrap();
+        assert_eq!(r.pos, Point::new(2, 4));
+        assert_eq!(r.velocity, Point::new(2, -3));
 
-        let mut r = Robot::new("p=2,4 v=2,-3");
+        let mut r = Robot::new("p=2,4 v=2,-3").unwrap();
         r.navigate(5, &HEIGHT, &WIDTH);
-        assert_eq!(r.pos, XY { x: 1, y: 3 });
+        assert_eq!(r.pos, Point::new(1, 3));
+
+        // Far enough that the move doesn't fit in an i32
+        let mut r = Robot::new("p=0,0 v=-100000,99999").unwrap();
+        r.navigate(1000000, &HEIGHT, &WIDTH);
+        assert_eq!(r.pos, Point::new(1, 4));
     }
 
     #[test]
     fn test_part_1() {
-        let ebhq = EBHQ::new(INPUT, HEIGHT, WIDTH);
+        let ebhq = EBHQ::new(EXAMPLE, HEIGHT, WIDTH).unwrap();
         assert_eq!(part1(&ebhq), 12);
     }
 
     #[test]
     #[should_panic]
     fn test_part_2() {
-        let ebhq = EBHQ::new(&INPUT, HEIGHT, WIDTH);
+        let ebhq = EBHQ::new(EXAMPLE, HEIGHT, WIDTH).unwrap();
         part2(&ebhq);
     }
 }
@@ -1,3 +1,5 @@
+use crate::day::{self, Day, Example, ParseError};
+use crate::utils::grid::{Grid, Point};
 use std::collections::HashSet;
 use std::fmt::{Display, Formatter};
 
@@ -6,6 +8,64 @@ const EMPTY: u8 = 0;
 const OBSTACLE: u8 = 1;
 const OBSTACLE_2: u8 = 2;
 
+const EXAMPLE_SMALL: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
+const EXAMPLE_LARGE: &str = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Puzzle;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE_SMALL, answers: [Some("2028"), None], params: &[] },
+        Example { input: EXAMPLE_LARGE, answers: [Some("10092"), Some("9021")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+}
+
 pub fn part1(p: &Puzzle) -> u64 {
     let mut pm = p.clone();
     p.moves.iter().for_each(|m| {
@@ -23,110 +83,72 @@ pub fn part2(p: &Puzzle) -> u64 {
     pm.gps()
 }
 
-pub fn generator(input: &str) -> Puzzle {
+pub fn generator(input: &str) -> Result<Puzzle, ParseError> {
     Puzzle::new(input)
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct XY {
-    x: i32,
-    y: i32,
-}
-
-impl XY {
-    fn apply(&mut self, m: &XY) {
-        self.x += m.x;
-        self.y += m.y;
-    }
-
-    fn revert(&mut self, m: &XY) {
-        self.x -= m.x;
-        self.y -= m.y;
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct Puzzle {
-    robot: XY,
-    map: Vec<Vec<u8>>,
-    moves: Vec<XY>,
+    robot: Point,
+    map: Grid<u8>,
+    moves: Vec<Point>,
 }
 
 impl Display for Puzzle {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut output = String::new();
-        self.map.iter().enumerate().for_each(|(y, row)| {
-            row.iter().enumerate().for_each(|(x, s)| {
-                match s {
-                    &WALL => output.push('#'),
-                    &OBSTACLE => output.push('['),
-                    &OBSTACLE_2 => output.push(']'),
-                    &EMPTY if y as i32 == self.robot.y && x as i32 == self.robot.x => output.push('@'),
-                    _ => output.push('.'),
-                }
-            });
-            output.push('\n');
+        let output = self.map.render(|p, s| {
+            match *s {
+                WALL => '#',
+                OBSTACLE => '[',
+                OBSTACLE_2 => ']',
+                EMPTY if p == self.robot => '@',
+                _ => '.',
+            }
         });
-        output.pop();
         write!(f, "{output}")
     }
 }
 
 impl Puzzle {
-    fn new(input: &str) -> Self {
-        let (map, moves) = input.split_once("\n\n").unwrap();
-        let mut robot: Option<XY> = None;
-        let map: Vec<Vec<u8>> = map.lines().enumerate().map(|(y, l)| {
-            l.chars().enumerate().map(|(x, c)| {
-                match c {
-                    '.' => EMPTY,
-                    '#' => WALL,
-                    'O' => OBSTACLE,
-                    '@' if robot.is_none() => {
-                        robot = Some(XY { x: x as i32, y: y as i32 });
-                        EMPTY
-                    }
-                    '@' => panic!("Found two positions of the robot"),
-                    _ => panic!("Invalid character in map: {}", c),
-                }
-            }).collect()
-        }).collect();
-        let moves: Vec<XY> = moves.lines().flat_map(|l| {
-            l.chars().map(|c| {
-                match c {
-                    '^' => XY { x: 0, y: -1 },
-                    '>' => XY { x: 1, y: 0 },
-                    'v' => XY { x: 0, y: 1 },
-                    '<' => XY { x: -1, y: 0 },
-                    _ => panic!("Unexpected character {c}")
-                }
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (map, moves) = day::split_once(input, "\n\n")?;
+        let (map, [robot]) = Grid::parse_with_markers(map, ['@'], |c| {
+            match c {
+                '.' | '@' => Some(EMPTY),
+                '#' => Some(WALL),
+                'O' => Some(OBSTACLE),
+                _ => None,
+            }
+        })?;
+        let moves: Vec<Point> = moves.lines().flat_map(|l| {
+            l.char_indices().map(|(i, c)| {
+                Point::from_arrow(c).ok_or_else(|| ParseError::unexpected(&l[i..]))
             })
-        }).collect();
+        }).collect::<Result<_, _>>()?;
 
-        Puzzle {
+        Ok(Puzzle {
             map,
-            robot: robot.unwrap(),
+            robot,
             moves,
-        }
+        })
     }
 
-    fn apply(&mut self, m: &XY) -> bool {
-        let mut pos = self.robot.clone();
-        pos.apply(m);
+    fn apply(&mut self, m: &Point) -> bool {
+        let mut pos = self.robot + *m;
         let mut times = 0;
-        while self.map[pos.y as usize][pos.x as usize] != EMPTY && self.map[pos.y as usize][pos.x as usize] != WALL {
+        while self.map[pos] != EMPTY && self.map[pos] != WALL {
             times += 1;
-            pos.apply(&m);
+            pos += *m;
         }
 
-        match self.map[pos.y as usize][pos.x as usize] {
+        match self.map[pos] {
             EMPTY => {
                 // Move everything forward
                 for _ in 0..times {
-                    self.map[pos.y as usize][pos.x as usize] = self.map[(pos.y - m.y) as usize][(pos.x - m.x) as usize];
-                    pos.revert(&m);
+                    self.map[pos] = self.map[pos - *m];
+                    pos -= *m;
                 }
-                self.map[pos.y as usize][pos.x as usize] = EMPTY;
+                self.map[pos] = EMPTY;
                 self.robot = pos;
                 true
             }
@@ -139,163 +161,118 @@ impl Puzzle {
     }
 
     fn gps(&self) -> u64 {
-        self.map.iter().enumerate().flat_map(|(y, l)| {
-            l.iter().enumerate().filter_map(move |(x, v)| {
-                if v == &OBSTACLE {
-                    Some(100 * y as u64 + x as u64)
-                } else {
-                    None
-                }
-            })
-        }).sum()
+        self.map.iter()
+            .filter(|(_, v)| **v == OBSTACLE)
+            .map(|(p, _)| 100 * p.y as u64 + p.x as u64)
+            .sum()
     }
 
-    fn add_check(&self, p: XY, to_check: &mut HashSet<XY>) {
-        match self.map[p.y as usize][p.x as usize] {
+    fn add_check(&self, p: Point, to_check: &mut HashSet<Point>) {
+        match self.map[p] {
             OBSTACLE => {
-                to_check.insert(XY { x: p.x + 1, y: p.y });
+                to_check.insert(p + Point::RIGHT);
             }
             OBSTACLE_2 => {
-                to_check.insert(XY { x: p.x - 1, y: p.y });
+                to_check.insert(p + Point::LEFT);
             }
             _ => {}
         }
         to_check.insert(p);
     }
 
-    fn apply_double(&mut self, m: &XY) -> bool {
+    fn apply_double(&mut self, m: &Point) -> bool {
         if m.y == 0 {
             return self.apply(m);
         }
 
         // Handle the vertical movement
-        let mut pos = self.robot.clone();
-        pos.apply(m);
         let mut to_check = HashSet::new();
         let mut to_change = Vec::new();
-        self.add_check(pos, &mut to_check);
+        self.add_check(self.robot + *m, &mut to_check);
 
         while !to_check.is_empty() {
-            let to_process: Vec<XY> = to_check.drain().collect();
+            let to_process: Vec<Point> = to_check.drain().collect();
             for pos in to_process {
-                match self.map[pos.y as usize][pos.x as usize] {
+                match self.map[pos] {
                     WALL => { return false; }
                     EMPTY => {}
-                    _ => {
-                        let mut pos = pos.clone();
-                        pos.apply(m);
-                        self.add_check(pos, &mut to_check);
-                    }
+                    _ => self.add_check(pos + *m, &mut to_check),
                 }
                 to_change.push(pos);
             }
         }
-        let change_items: HashSet<XY> = to_change.clone().into_iter().collect();
+        let change_items: HashSet<Point> = to_change.iter().copied().collect();
 
-        while !to_change.is_empty() {
-            let p = to_change.pop().unwrap();
-            self.map[p.y as usize][p.x as usize] = if change_items.contains(&XY { x: p.x - m.x, y: p.y - m.y }) {
-                self.map[(p.y - m.y) as usize][(p.x - m.x) as usize]
+        while let Some(p) = to_change.pop() {
+            self.map[p] = if change_items.contains(&(p - *m)) {
+                self.map[p - *m]
             } else {
                 EMPTY
             };
         }
-        self.robot.apply(m);
+        self.robot += *m;
         true
     }
 
     fn double(&mut self) {
-        self.map.iter_mut().for_each(|m| {
-            let mut n = vec![EMPTY; m.len() * 2];
-            m.iter().enumerate().for_each(|(x, y)| {
-                match y {
-                    &WALL => {
-                        n[x * 2] = WALL;
-                        n[x * 2 + 1] = WALL;
-                    }
-                    &OBSTACLE => {
-                        n[x * 2] = OBSTACLE;
-                        n[x * 2 + 1] = OBSTACLE_2;
-                    }
-                    _ => {}
+        let mut doubled = Grid::new(self.map.width() * 2, self.map.height(), EMPTY);
+        self.map.iter().for_each(|(p, s)| {
+            let left = Point::new(p.x * 2, p.y);
+            match *s {
+                WALL => {
+                    doubled[left] = WALL;
+                    doubled[left + Point::RIGHT] = WALL;
+                }
+                OBSTACLE => {
+                    doubled[left] = OBSTACLE;
+                    doubled[left + Point::RIGHT] = OBSTACLE_2;
                 }
-            });
-            *m = n;
+                _ => {}
+            }
         });
+        self.map = doubled;
         self.robot.x *= 2;
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2, EMPTY, OBSTACLE, WALL, XY};
-
-    const INPUT_SMALL: &str = "########
-#..O.O.#
-##@.O..#
-#...O..#
-#.#.O..#
-#...O..#
-#......#
-########
-
-<^^>>>vv<v>>v<<";
-    const INPUT_LARGE: &str = "##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########
-
-<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
-vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
-><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
-<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
-^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
-^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
->^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
-<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
-^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
-v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+    use super::{generator, part1, part2, EMPTY, OBSTACLE, WALL, Point, EXAMPLE_SMALL, EXAMPLE_LARGE};
 
     #[test]
     fn test_generator() {
-        let p = generator(&INPUT_SMALL);
-        assert_eq!(p.robot, XY { x: 2, y: 2 });
-        assert_eq!(p.map.len(), 8);
-        assert_eq!(p.map[0].len(), 8);
-        assert_eq!(p.map[0][0], WALL);
-        assert_eq!(p.map[2][1], WALL);
-        assert_eq!(p.map[1][3], OBSTACLE);
-
-        let p = generator(&INPUT_LARGE);
+        let p = generator(EXAMPLE_SMALL).unwrap();
+        assert_eq!(p.robot, Point::new(2, 2));
+        assert_eq!(p.map.height(), 8);
+        assert_eq!(p.map.width(), 8);
+        assert_eq!(p.map[Point::new(0, 0)], WALL);
+        assert_eq!(p.map[Point::new(1, 2)], WALL);
+        assert_eq!(p.map[Point::new(3, 1)], OBSTACLE);
+
+        let p = generator(EXAMPLE_LARGE).unwrap();
         assert_eq!(p.moves.len(), 700);
     }
 
     #[test]
     fn test_moves() {
-        let mut p = generator(&INPUT_SMALL);
-        assert_eq!(p.apply(&XY { x: -1, y: 0 }), false);
-        assert_eq!(p.apply(&XY { x: 0, y: -1 }), true);
-        assert_eq!(p.robot, XY { x: 2, y: 1 });
-        assert_eq!(OBSTACLE, p.map[1][3]);
-        assert_eq!(EMPTY, p.map[1][4]);
-        assert_eq!(p.apply(&XY { x: 1, y: 0 }), true);
-        assert_eq!(p.robot, XY { x: 3, y: 1 });
-        assert_eq!(EMPTY, p.map[1][3]);
-        assert_eq!(OBSTACLE, p.map[1][4]);
+        let mut p = generator(EXAMPLE_SMALL).unwrap();
+        assert_eq!(p.apply(&Point::new(-1, 0)), false);
+        assert_eq!(p.apply(&Point::new(0, -1)), true);
+        assert_eq!(p.robot, Point::new(2, 1));
+        assert_eq!(OBSTACLE, p.map[Point::new(3, 1)]);
+        assert_eq!(EMPTY, p.map[Point::new(4, 1)]);
+        assert_eq!(p.apply(&Point::new(1, 0)), true);
+        assert_eq!(p.robot, Point::new(3, 1));
+        assert_eq!(EMPTY, p.map[Point::new(3, 1)]);
+        assert_eq!(OBSTACLE, p.map[Point::new(4, 1)]);
     }
 
     #[test]
     fn test_part_1() {
-        let p = generator(&INPUT_SMALL);
+        let p = generator(EXAMPLE_SMALL).unwrap();
         assert_eq!(part1(&p), 2028);
 
-        let p = generator(&INPUT_LARGE);
+        let p = generator(EXAMPLE_LARGE).unwrap();
         assert_eq!(part1(&p), 10092);
     }
 
@@ -309,11 +286,11 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 #.....#
 #######
 
-<vv<<^^<<^^");
+<vv<<^^<<^^").unwrap();
         p.double();
-        assert_eq!(p.map.len(), 7);
-        assert_eq!(p.map[0].len(), 14);
-        assert_eq!(p.robot, XY { x: 10, y: 3 });
+        assert_eq!(p.map.height(), 7);
+        assert_eq!(p.map.width(), 14);
+        assert_eq!(p.robot, Point::new(10, 3));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##..........##
@@ -321,7 +298,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##....[]....##
 ##..........##
 ##############");
-        p.apply_double(&XY { x: -1, y: 0 });
+        p.apply_double(&Point::new(-1, 0));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##..........##
@@ -329,7 +306,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##....[]....##
 ##..........##
 ##############");
-        p.apply_double(&XY { x: 0, y: 1 });
+        p.apply_double(&Point::new(0, 1));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##..........##
@@ -337,7 +314,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##....[].@..##
 ##..........##
 ##############");
-        p.apply_double(&XY { x: 0, y: 1 });
+        p.apply_double(&Point::new(0, 1));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##..........##
@@ -345,7 +322,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##....[]....##
 ##.......@..##
 ##############");
-        p.apply_double(&XY { x: -1, y: 0 });
+        p.apply_double(&Point::new(-1, 0));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##..........##
@@ -354,7 +331,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##......@...##
 ##############");
 
-        p.apply_double(&XY { x: -1, y: 0 });
+        p.apply_double(&Point::new(-1, 0));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##..........##
@@ -363,7 +340,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##.....@....##
 ##############");
 
-        p.apply_double(&XY { x: 0, y: -1 });
+        p.apply_double(&Point::new(0, -1));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##...[][]...##
@@ -372,7 +349,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##..........##
 ##############");
 
-        p.apply_double(&XY { x: 0, y: -1 });
+        p.apply_double(&Point::new(0, -1));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##...[][]...##
@@ -381,8 +358,8 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##..........##
 ##############");
 
-        p.apply_double(&XY { x: -1, y: 0 });
-        p.apply_double(&XY { x: -1, y: 0 });
+        p.apply_double(&Point::new(-1, 0));
+        p.apply_double(&Point::new(-1, 0));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##...[][]...##
@@ -390,7 +367,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##...@......##
 ##..........##
 ##############");
-        p.apply_double(&XY { x: 0, y: -1 });
+        p.apply_double(&Point::new(0, -1));
         assert_eq!(p.to_string(), "##############
 ##......##..##
 ##...[][]...##
@@ -398,7 +375,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##..........##
 ##..........##
 ##############");
-        p.apply_double(&XY { x: 0, y: -1 });
+        p.apply_double(&Point::new(0, -1));
         assert_eq!(p.to_string(), "##############
 ##...[].##..##
 ##...@.[]...##
@@ -410,7 +387,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 
     #[test]
     fn test_part_2() {
-        let p = generator(&INPUT_LARGE);
+        let p = generator(EXAMPLE_LARGE).unwrap();
         assert_eq!(part2(&p), 9021);
     }
 }
@@ -1,264 +1,186 @@
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use crate::day::{Day, Example, ParseError};
+use std::collections::{HashMap, HashSet};
 use crate::day16::Space::{EMPTY, WALL};
+use crate::utils::grid::{Grid, Point};
+use crate::utils::search::{self, Graph, Search};
 
-const NEIGHBORS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const EXAMPLE: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+const EXAMPLE_2: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Maze;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("7036"), Some("45")], params: &[] },
+        Example { input: EXAMPLE_2, answers: [Some("11048"), Some("64")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
 
 pub fn part1(m: &Maze) -> u32 {
-    m.score::<false>()
+    m.search().goal_distance().unwrap()
 }
 
 pub fn part2(m: &Maze) -> u32 {
-    m.score::<true>()
+    let search = m.search();
+    let best_spots: HashSet<Point> = search.on_shortest_paths(&search.goals).into_iter()
+        .map(|(pos, _)| pos)
+        .collect();
+    best_spots.len() as u32
 }
 
-pub fn generator(input: &str) -> Maze {
+pub fn generator(input: &str) -> Result<Maze, ParseError> {
     Maze::new(input)
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
 enum Space {
     WALL,
-    EMPTY(Vec<XY>),
-}
-
-impl Space {}
-
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
-struct XY {
-    x: i32,
-    y: i32,
-}
-
-impl XY {
-    fn apply(&mut self, dir: &XY) {
-        self.x += dir.x;
-        self.y += dir.y;
-    }
+    EMPTY(Vec<Point>),
 }
 
 pub struct Maze {
-    map: Vec<Vec<Space>>,
-    start: XY,
-    dir: XY,
-    end: XY,
+    map: Grid<Space>,
+    start: Point,
+    dir: Point,
+    end: Point,
 }
 
 impl Maze {
-    fn new(input: &str) -> Self {
-        let mut start = None;
-        let mut end = None;
-
-        let char_map: Vec<Vec<char>> = input.lines().enumerate().map(|(y, l)| {
-            l.chars().enumerate().map(|(x, c)| {
-                match c {
-                    'S' => start = Some(XY { x: x as i32, y: y as i32 }),
-                    'E' => end = Some(XY { x: x as i32, y: y as i32 }),
-                    _ => {}
-                }
-                c
-            }).collect()
-        }).collect();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (char_map, [start, end]) = Grid::parse_with_markers(input, ['S', 'E'], |c| {
+            "#.SE".contains(c).then_some(c)
+        })?;
+        let (width, height) = (char_map.width() as i32, char_map.height() as i32);
+        if let Some((p, _)) = char_map.iter().find(|(p, c)| {
+            **c != '#' && (p.y == 0 || p.x == 0 || p.y == height - 1 || p.x == width - 1)
+        }) {
+            // Every character is one of the above, so columns are bytes
+            let line = input.lines().nth(p.y as usize).unwrap();
+            return Err(ParseError::invalid(&line[p.x as usize..], "maze must be surrounded by walls"));
+        }
 
-        let mut map = vec![vec![WALL; char_map[0].len()]; char_map.len()];
-        char_map.iter().enumerate().for_each(|(y, line)| {
-            line.iter().enumerate().for_each(|(x, c)| {
-                if c == &'#' { return; }
-                let paths: Vec<XY> = NEIGHBORS.iter().filter_map(|(dx, dy)| {
-                    if char_map[(y as i32 + dy) as usize][(x as i32 + dx) as usize] != '#' {
-                        Some(XY { x: *dx, y: *dy })
-                    } else {
-                        None
-                    }
-                }).collect();
-                map[y][x] = EMPTY(paths);
-            });
+        let mut map = char_map.map(|_| WALL);
+        char_map.iter().filter(|(_, c)| **c != '#').for_each(|(p, _)| {
+            let paths: Vec<Point> = Point::ORTHOGONAL.into_iter()
+                .filter(|d| char_map[p + *d] != '#')
+                .collect();
+            map[p] = EMPTY(paths);
         });
 
-        Maze {
+        Ok(Maze {
             map,
-            start: start.unwrap(),
-            dir: XY { x: 1, y: 0 },
-            end: end.unwrap(),
-        }
-    }
-
-    fn score<const COUNT_BEST_POS: bool>(&self) -> u32 {
-        let mut path = Path::new(self.start.clone(), self.dir.clone());
-        path.visited.push(self.start.clone());
-        let mut best_scores = HashMap::new();
-        let mut best_spots: HashSet<XY> = HashSet::new();
-        let mut best_score = None;
-        let mut heap = BinaryHeap::new();
-        heap.push(path);
-
-        while !heap.is_empty() {
-            let p = heap.pop().unwrap();
-            if COUNT_BEST_POS && best_score.is_some() && p.score > best_score.unwrap() {
-                break;
-            } else if p.pos == self.end {
-                best_score = Some(p.score);
-                if !COUNT_BEST_POS {
-                    break;
-                }
-                for pos in p.visited.iter() {
-                    best_spots.insert(pos.clone());
-                }
-            }
-
-            match best_scores.get_mut(&(p.pos.clone(), p.dir.clone())) {
-                None => {
-                    best_scores.insert((p.pos.clone(), p.dir.clone()), p.score);
-                }
-                Some(score) if *score > p.score || (*score == p.score && COUNT_BEST_POS) => {
-                    *score = p.score;
-                }
-                _ => { continue }
-            }
-
-            match &self.map[p.pos.y as usize][p.pos.x as usize] {
-                EMPTY(choices) => {
-                    choices.iter().filter(|&c| {
-                        let mut next = p.pos.clone();
-                        next.apply(c);
-                        (c.x + p.dir.x != 0) || (c.y + p.dir.y != 0)
-                    })
-                        .for_each(|c| {
-                            match c {
-                                c if c == &p.dir => {
-                                    let mut p = p.clone();
-                                    p.pos.apply(&c);
-                                    p.score += 1;
-                                    p.visited.push(p.pos.clone());
-                                    heap.push(p);
-                                }
-                                _ => {
-                                    let mut p = p.clone();
-                                    p.dir.x = c.x;
-                                    p.dir.y = c.y;
-                                    p.score += 1001;
-                                    p.pos.apply(&c);
-                                    p.visited.push(p.pos.clone());
-                                    heap.push(p);
-                                }
-                            }
-                        })
-                }
-                _ => unreachable!()
-            }
-        }
-
-        if COUNT_BEST_POS {
-            best_spots.len() as u32
-        } else {
-            best_score.unwrap()
-        }
-    }
-}
-
-#[derive(Debug, Clone)]
-struct Path {
-    score: u32,
-    pos: XY,
-    dir: XY,
-    visited: Vec<XY>,
-}
-
-impl Path {
-    fn new(pos: XY, dir: XY) -> Self {
-        Path {
-            pos,
-            dir,
-            score: 0,
-            visited: Vec::new(),
-        }
+            start,
+            dir: Point::RIGHT,
+            end,
+        })
     }
-}
 
-impl PartialEq<Self> for Path {
-    fn eq(&self, other: &Self) -> bool {
-        self.score == other.score
+    /// Search for the best paths from the start to the end, where a node is
+    /// a position and the direction the reindeer faces there.
+    fn search(&self) -> Search<Maze> {
+        search::astar(self, (self.start, self.dir), |(pos, _)| *pos == self.end,
+                      |(pos, _)| pos.manhattan(self.end))
     }
 }
 
-impl Eq for Path {}
+impl Graph for Maze {
+    type Node = (Point, Point);
+    type Map<V: Clone> = HashMap<(Point, Point), V>;
 
-impl PartialOrd<Self> for Path {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.score.cmp(&other.score).reverse())
+    fn map<V: Clone>(&self) -> Self::Map<V> {
+        HashMap::new()
     }
-}
 
-impl Ord for Path {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+    fn neighbors(&self, node: &(Point, Point)) -> impl Iterator<Item = ((Point, Point), u32)> {
+        let (pos, dir) = *node;
+        let choices = match &self.map[pos] {
+            EMPTY(choices) => choices,
+            WALL => unreachable!(),
+        };
+        choices.iter()
+            .filter(move |&&c| c != -dir)
+            .map(move |&c| ((pos + c, c), if c == dir { 1 } else { 1001 }))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day16::Space::{EMPTY, WALL};
-    use super::{generator, part1, part2, XY};
-
-    const INPUT: &str = "###############
-#.......#....E#
-#.#.###.#.###.#
-#.....#.#...#.#
-#.###.#####.#.#
-#.#.#.......#.#
-#.#.#####.###.#
-#...........#.#
-###.#.#####.#.#
-#...#.....#.#.#
-#.#.#.###.#.#.#
-#.....#...#.#.#
-#.###.#.#.#.#.#
-#S..#.....#...#
-###############";
-    const INPUT_2: &str = "#################
-#...#...#...#..E#
-#.#.#.#.#.#.#.#.#
-#.#.#.#...#...#.#
-#.#.#.#.###.#.#.#
-#...#.#.#.....#.#
-#.#.#.#.#.#####.#
-#.#...#.#.#.....#
-#.#.#####.#.###.#
-#.#.#.......#...#
-#.#.###.#####.###
-#.#.#...#.....#.#
-#.#.#.#####.###.#
-#.#.#.........#.#
-#.#.#.#########.#
-#S#.............#
-#################";
+    use super::{generator, part1, part2, Point, EXAMPLE, EXAMPLE_2};
 
     #[test]
     fn test_generator() {
-        let m = generator(&INPUT);
-        assert_eq!(m.dir, XY { x: 1, y: 0 });
-        assert_eq!(m.end, XY { x: 13, y: 1 });
-        assert_eq!(m.start, XY { x: 1, y: 13 });
-        assert_eq!(m.map[0][0], WALL);
-        assert_eq!(m.map[1][13], EMPTY(vec![XY { x: -1, y: 0 }, XY { x: 0, y: 1 }]));
-        assert_eq!(m.map[13][1], EMPTY(vec![XY { x: 1, y: 0 }, XY { x: 0, y: -1 }]));
+        let m = generator(EXAMPLE).unwrap();
+        assert_eq!(m.dir, Point::new(1, 0));
+        assert_eq!(m.end, Point::new(13, 1));
+        assert_eq!(m.start, Point::new(1, 13));
+        assert_eq!(m.map[Point::new(0, 0)], WALL);
+        assert_eq!(m.map[Point::new(13, 1)], EMPTY(vec![Point::DOWN, Point::LEFT]));
+        assert_eq!(m.map[Point::new(1, 13)], EMPTY(vec![Point::UP, Point::RIGHT]));
     }
 
     #[test]
     fn test_part_1() {
-        let m = generator(&INPUT);
+        let m = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&m), 7036);
 
-        let m = generator(&INPUT_2);
+        let m = generator(EXAMPLE_2).unwrap();
         assert_eq!(part1(&m), 11048);
     }
 
     #[test]
     fn test_part_2() {
-        let m = generator(&INPUT);
+        let m = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&m), 45);
 
-        let m = generator(&INPUT_2);
+        let m = generator(EXAMPLE_2).unwrap();
         assert_eq!(part2(&m), 64);
     }
 }
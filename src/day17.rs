@@ -1,38 +1,59 @@
+use crate::day::{self, Day, Example, ParseError};
 use crate::day17::Instruction::{ADV, BDV, BST, BXC, BXL, CDV, JNZ, OUT};
 
+pub mod asm;
+pub mod disasm;
+pub mod repl;
+pub mod solve;
+pub mod trace;
+
+const EXAMPLE: &str = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+
+const EXAMPLE_2: &str = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Debugger;
+    type Output1 = String;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("4,6,3,5,6,3,5,2,1,0"), None], params: &[] },
+        Example { input: EXAMPLE_2, answers: [None, Some("117440")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> String {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+}
+
 pub fn part1(d: &Debugger) -> String {
     let mut d = d.clone();
     d.run().iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",")
 }
 
 pub fn part2(d: &Debugger) -> u64 {
-    let mut d = d.clone();
-    let expected = d.to_instructions();
-    let bits = d.optimize().unwrap();
-
-    find_value(&mut d, bits, 0, expected.len() - 1, &expected).unwrap()
-}
-
-fn find_value(d: &mut Debugger, bits: u32, a_value: u64, a_idx: usize, expected: &Vec<u64>) -> Option<u64> {
-    for v in 0..2u64.pow(bits) {
-        let curr = a_value | (v << (bits * a_idx as u32));
-        if curr == 0 { continue; }
-        d.state.reset(curr);
-        let o = d.run();
-        if o.len() == expected.len() && o[a_idx..] == expected[a_idx..] {
-            if a_idx == 0 {
-                return Some(curr);
-            }
-            match find_value(d, bits, curr, a_idx - 1, expected) {
-                Some(v) => return Some(v),
-                None => continue,
-            }
-        }
-    }
-    None
+    solve::solve(d, &d.to_instructions()).expect("no value of A makes the program output itself")
 }
 
-pub fn generator(input: &str) -> Debugger {
+pub fn generator(input: &str) -> Result<Debugger, ParseError> {
     Debugger::new(input)
 }
 
@@ -43,137 +64,70 @@ pub struct Debugger {
 }
 
 impl Debugger {
-    fn new(input: &str) -> Self {
-        let (state, program) = input.trim().split_once("\n\n").unwrap();
-        let program: Vec<&str> = program.split_once(' ').unwrap().1
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (state, program) = day::split_once(input.trim(), "\n\n")?;
+        let program: Vec<&str> = day::split_once(program, " ")?.1
             .split(',')
             .collect();
-        let program = program.chunks(2).map(|v| Instruction::new(v[0], v[1])).collect();
+        let program = program.chunks(2).map(|v| match v {
+            [inst, op] => Instruction::new(inst, op),
+            _ => Err(ParseError::missing(&v[0][v[0].len()..], "operand")),
+        }).collect::<Result<_, _>>()?;
 
-        Debugger {
+        Ok(Debugger {
             program,
-            state: State::new(state),
-        }
+            state: State::new(state)?,
+        })
     }
 
     fn run(&mut self) -> Vec<u64> {
+        self.run_traced(&mut (), usize::MAX)
+    }
+
+    /// Run the program from the current state for at most limit
+    /// instructions, telling the tracer about every instruction executed.
+    pub fn run_traced(&mut self, tracer: &mut impl Tracer, limit: usize) -> Vec<u64> {
         let mut output: Vec<u64> = Vec::new();
-        while self.state.ip < self.program.len() as u64 {
-            if let Some(o) = self.program[self.state.ip as usize].perform(&mut self.state) {
+        for _ in 0..limit {
+            let Some(instruction) = self.program.get(self.state.ip as usize) else { break };
+            let before = self.state.clone();
+            let o = instruction.perform(&mut self.state);
+            tracer.step(instruction, &before, &self.state, o);
+            if let Some(o) = o {
                 output.push(o);
             }
         }
         output
     }
 
-    fn optimize(&self) -> Option<u32> {
-        let mut found_adv = None;
-
-        let deps: Vec<(u64, (u64, u64))> = self.program.iter().map(|i| {
-            match i {
-                ADV(o) if o < &4 => {
-                    found_adv = Some(*o as u32);
-                    (4, (4, *o))
-                }
-                ADV(o) => (4, (4, *o)),
-                BXL(o) => (5, (5, *o)),
-                BST(o) => (5, (0, *o)),
-                JNZ(_) => (0, (0, 0)),
-                BXC(_) => (5, (5, 6)),
-                OUT(_) => (0, (0, 0)),
-                BDV(o) => (5, (4, *o)),
-                CDV(o) => (5, (4, *o)),
-            }
-        }).collect();
-
-        match deps.iter().find(|(t, _)| t == &4).unwrap() {
-            (_, (o1, o2)) if o1 > &5 || o2 > &5 => return None,
-            _ => {}
-        }
-
-        let first_b = deps.iter().enumerate()
-            .find(|(_, (t, _))| t == &5)
-            .map(|(i, (_, (o1, o2)))| (i, o1 == &6 || o2 == &6 || o1 == &5 || o2 == &5));
-        let first_c = deps.iter().enumerate()
-            .find(|(_, (t, _))| t == &6)
-            .map(|(i, (_, (o1, o2)))| (i, o1 == &6 || o2 == &6 || o1 == &5 || o2 == &5));
-
-        match (first_b, first_c) {
-            (None, None) => found_adv,
-            (Some((i, true)), Some((j, false))) if i > j => found_adv,
-            (Some((i, false)), Some((j, true))) if j > i => found_adv,
-            (Some((_, false)), None) => found_adv,
-            (None, Some((_, false))) => found_adv,
-            _ => None
-        }
-    }
-
-    fn to_instructions(&self) -> Vec<u64> {
-        let mut expected = Vec::new();
-        for i in &self.program {
-            match i {
-                ADV(op) => {
-                    expected.push(0);
-                    expected.push(*op);
-                }
-                BXL(op) => {
-                    expected.push(1);
-                    expected.push(*op);
-                }
-                BST(op) => {
-                    expected.push(2);
-                    expected.push(*op);
-                }
-                JNZ(op) => {
-                    expected.push(3);
-                    expected.push(*op);
-                }
-                BXC(op) => {
-                    expected.push(4);
-                    expected.push(*op);
-                }
-                OUT(op) => {
-                    expected.push(5);
-                    expected.push(*op);
-                }
-                BDV(op) => {
-                    expected.push(6);
-                    expected.push(*op);
-                }
-                CDV(op) => {
-                    expected.push(7);
-                    expected.push(*op);
-                }
-            }
-        }
-        expected
+    /// The program as the numbers it was written as.
+    pub fn to_instructions(&self) -> Vec<u64> {
+        self.program.iter().flat_map(Instruction::encode).collect()
     }
 }
 
-#[derive(Debug, Default, Clone)]
-struct State {
-    a: u64,
-    b: u64,
-    c: u64,
-    ip: u64,
+/// The registers, and the index of the next instruction.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct State {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+    pub ip: u64,
 }
 
 impl State {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut state = State::default();
         let mut lines = input.lines();
-        state.a = lines.next().unwrap().rsplit_once(' ').unwrap().1.parse().unwrap();
-        state.b = lines.next().unwrap().rsplit_once(' ').unwrap().1.parse().unwrap();
-        state.c = lines.next().unwrap().rsplit_once(' ').unwrap().1.parse().unwrap();
-
-        state
-    }
-
-    fn reset(&mut self, a: u64) {
-        self.a = a;
-        self.b = 0;
-        self.c = 0;
-        self.ip = 0;
+        let mut register = |name: &str| {
+            let line = lines.next().ok_or_else(|| ParseError::missing(&input[input.len()..], name))?;
+            day::parse(day::rsplit_once(line, " ")?.1)
+        };
+        state.a = register("register A")?;
+        state.b = register("register B")?;
+        state.c = register("register C")?;
+
+        Ok(state)
     }
 
     fn combo(&self, op: &u64) -> u64 {
@@ -187,8 +141,20 @@ impl State {
     }
 }
 
+/// Watches a program run, being told about each instruction after it's
+/// executed, with the state before and after it and what it output.
+pub trait Tracer {
+    fn step(&mut self, instruction: &Instruction, before: &State, after: &State,
+            output: Option<u64>);
+}
+
+/// Runs without watching.
+impl Tracer for () {
+    fn step(&mut self, _: &Instruction, _: &State, _: &State, _: Option<u64>) {}
+}
+
 #[derive(Debug, Eq, PartialEq, Clone)]
-enum Instruction {
+pub enum Instruction {
     ADV(u64),
     BXL(u64),
     BST(u64),
@@ -200,28 +166,48 @@ enum Instruction {
 }
 
 impl Instruction {
-    fn new(inst: &str, op: &str) -> Self {
-        let inst = inst.parse::<u64>().unwrap();
-        let op = op.parse::<u64>().unwrap();
+    fn new(inst: &str, op: &str) -> Result<Self, ParseError> {
+        let code = day::parse::<u64>(inst)?;
+        let operand = day::parse::<u64>(op)?;
+        if operand > 7 {
+            return Err(ParseError::invalid(op, "operands must be 3-bit numbers"));
+        }
+        let inst = match code {
+            0 => ADV(operand),
+            1 => BXL(operand),
+            2 => BST(operand),
+            3 => JNZ(operand),
+            4 => BXC(operand),
+            5 => OUT(operand),
+            6 => BDV(operand),
+            7 => CDV(operand),
+            _ => return Err(ParseError::invalid(inst, "opcodes must be 3-bit numbers")),
+        };
         match inst {
-            0 => ADV(op),
-            1 => BXL(op),
-            2 => BST(op),
-            3 => JNZ(op),
-            4 => BXC(op),
-            5 => OUT(op),
-            6 => BDV(op),
-            7 => CDV(op),
-            _ => unreachable!()
+            ADV(7) | BST(7) | OUT(7) | BDV(7) | CDV(7) =>
+                Err(ParseError::invalid(op, "combo operand 7 is reserved")),
+            _ => Ok(inst),
+        }
+    }
+
+    /// The opcode and operand that the instruction is written as.
+    fn encode(&self) -> [u64; 2] {
+        match *self {
+            ADV(op) => [0, op],
+            BXL(op) => [1, op],
+            BST(op) => [2, op],
+            JNZ(op) => [3, op],
+            BXC(op) => [4, op],
+            OUT(op) => [5, op],
+            BDV(op) => [6, op],
+            CDV(op) => [7, op],
         }
     }
 
     fn perform(&self, state: &mut State) -> Option<u64> {
         state.ip += 1;
         match self {
-            ADV(op) => {
-                state.a >>= state.combo(op);
-            }
+            ADV(op) => state.a = shift(state.a, state.combo(op)),
             BXL(op) => state.b ^= op,
             BST(op) => state.b = state.combo(op) % 8,
             JNZ(op) => {
@@ -233,32 +219,27 @@ impl Instruction {
             OUT(op) => {
                 return Some(state.combo(op) % 8)
             }
-            BDV(op) => state.b = state.a / 2u64.pow(state.combo(op) as u32),
-            CDV(op) => state.c = state.a / 2u64.pow(state.combo(op) as u32),
+            BDV(op) => state.b = shift(state.a, state.combo(op)),
+            CDV(op) => state.c = shift(state.a, state.combo(op)),
         }
         None
     }
 }
 
+/// Divide by 2 to the power of n, which shifts every bit out from n = 64.
+fn shift(value: u64, n: u64) -> u64 {
+    u32::try_from(n).ok().and_then(|n| value.checked_shr(n)).unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
+    use super::{generator, part1, part2, Solution, EXAMPLE, EXAMPLE_2};
+    use crate::day::Day;
     use crate::day17::Instruction::ADV;
 
-    const INPUT: &str = "Register A: 729
-Register B: 0
-Register C: 0
-
-Program: 0,1,5,4,3,0";
-    const INPUT_2: &str = "Register A: 2024
-Register B: 0
-Register C: 0
-
-Program: 0,3,5,4,3,0";
-
     #[test]
     fn test_generator() {
-        let d = generator(&INPUT);
+        let d = generator(EXAMPLE).unwrap();
         assert_eq!(d.program.len(), 3);
         assert_eq!(d.program[0], ADV(1));
         assert_eq!(d.state.a, 729);
@@ -269,57 +250,51 @@ Program: 0,3,5,4,3,0";
 
     #[test]
     fn test_part_1() {
-        let d = generator(&INPUT);
+        let d = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&d), "4,6,3,5,6,3,5,2,1,0");
 
         let d = generator("Register A: 10
 Register B: 0
 Register C: 0
 
-Program: 5,0,5,1,5,4");
+Program: 5,0,5,1,5,4").unwrap();
         assert_eq!(part1(&d), "0,1,2");
 
         let d = generator("Register A: 2024
 Register B: 0
 Register C: 0
 
-Program: 0,1,5,4,3,0");
+Program: 0,1,5,4,3,0").unwrap();
         assert_eq!(part1(&d), "4,2,5,6,7,7,7,7,3,1,0");
 
         let d = generator("Register A: 105981155568026
 Register B: 0
 Register C: 0
 
-Program: 2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0");
+Program: 2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0").unwrap();
         assert_eq!(part1(&d), "2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0");
     }
 
     #[test]
-    fn test_optimize() {
-        let d = generator(&INPUT);
-        assert_eq!(Some(1), d.optimize());
-
-        let d = generator(&INPUT_2);
-        assert_eq!(Some(3), d.optimize());
-
-        let d = generator("Register A: 2024
+    fn test_invalid_input() {
+        let err = Solution::parse("Register A: 729
 Register B: 0
 Register C: 0
 
-Program: 0,4,5,4,3,0");
-        assert_eq!(None, d.optimize());
+Program: 0,1,5,7,3,0").err().unwrap();
+        assert_eq!((5, 16), (err.line, err.column));
+        assert_eq!("line 5 column 16: combo operand 7 is reserved", err.to_string());
 
-        let d = generator("Register A: 2024
-Register B: 0
+        let err = Solution::parse("Register A: 729
 Register C: 0
 
-Program: 0,3,1,3,5,4,3,0");
-        assert_eq!(None, d.optimize());
+Program: 0,1,5,4,3,0").err().unwrap();
+        assert_eq!((2, 14), (err.line, err.column));
     }
 
     #[test]
     fn test_part_2() {
-        let d = generator(&INPUT_2);
+        let d = generator(EXAMPLE_2).unwrap();
         assert_eq!(117440, part2(&d));
     }
 }
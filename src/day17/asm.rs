@@ -0,0 +1,184 @@
+//! Assemble day 17 programs from mnemonics, so that test programs don't
+//! have to be written as digits. The syntax is that of the disassembler's
+//! listing, so a listing assembles back into the program it came from:
+//!
+//! ```text
+//! a = 729         ; optional registers, which make a whole puzzle input
+//! loop:
+//!     adv 1       ; combo operands are 0 to 3, or a, b or c
+//!     out a
+//!     jnz loop    ; jump targets are labels or addresses
+//! ```
+
+use crate::day::{self, ParseError};
+use crate::day17::disasm::MNEMONICS;
+use std::collections::HashMap;
+
+/// An assembled program, and the registers it starts with if any were set.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Program {
+    pub registers: Option<[u64; 3]>,
+    pub code: Vec<u64>,
+}
+
+impl Program {
+    /// The program in the puzzle's input format: a whole input when the
+    /// registers were set, otherwise only the program line.
+    pub fn to_input(&self) -> String {
+        let code: Vec<String> = self.code.iter().map(u64::to_string).collect();
+        match self.registers {
+            Some([a, b, c]) => format!("Register A: {a}\nRegister B: {b}\nRegister C: {c}\n\n\
+                                        Program: {}\n", code.join(",")),
+            None => format!("Program: {}\n", code.join(",")),
+        }
+    }
+}
+
+/// An operand that's a 3-bit number, or one of the names that stand for
+/// a number.
+fn operand(text: &str, names: &[(&str, u64)]) -> Result<u64, ParseError> {
+    if let Some((_, value)) = names.iter().find(|(name, _)| text.eq_ignore_ascii_case(name)) {
+        return Ok(*value);
+    }
+    let value = day::parse::<u64>(text)?;
+    if value > 7 {
+        return Err(ParseError::invalid(text, "operands must be 3-bit numbers"));
+    }
+    Ok(value)
+}
+
+/// A combo operand, which is a number from 0 to 3 or a register.
+fn combo(text: &str) -> Result<u64, ParseError> {
+    match operand(text, &[("a", 4), ("b", 5), ("c", 6)])? {
+        7 => Err(ParseError::invalid(text, "combo operand 7 is reserved")),
+        value => Ok(value),
+    }
+}
+
+fn is_label(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn assemble_lines(text: &str) -> Result<Program, ParseError> {
+    let mut registers = None;
+    // The instructions as their opcodes and operand text, and the labels
+    let mut instructions: Vec<(u64, &str)> = Vec::new();
+    let mut labels: HashMap<&str, u64> = HashMap::new();
+    for line in text.lines() {
+        let mut line = line.split([';', '#']).next().unwrap().trim();
+        if let Some((label, rest)) = line.split_once(':') {
+            let label = label.trim();
+            if !is_label(label) {
+                return Err(ParseError::invalid(label, "labels are letters, digits and _"));
+            }
+            if labels.insert(label, 2 * instructions.len() as u64).is_some() {
+                return Err(ParseError::invalid(label, "the label is already defined"));
+            }
+            line = rest.trim();
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if let Some((register, value)) = line.split_once('=') {
+            let register = register.trim();
+            let index = ["a", "b", "c"].iter().position(|r| register.eq_ignore_ascii_case(r))
+                .ok_or_else(|| ParseError::invalid(register, "registers are a, b and c"))?;
+            registers.get_or_insert([0; 3])[index] = day::parse(value.trim())?;
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let mnemonic = words.next().unwrap();
+        let code = MNEMONICS.iter().position(|m| mnemonic.eq_ignore_ascii_case(m))
+            .ok_or_else(|| ParseError::invalid(mnemonic, "unknown instruction"))?;
+        let op = match words.next() {
+            Some(op) => op,
+            // The operand of bxc is ignored, so it can be left out
+            None if code == 4 => "0",
+            None => return Err(ParseError::missing(&line[line.len()..], "operand")),
+        };
+        if let Some(extra) = words.next() {
+            return Err(ParseError::unexpected(extra));
+        }
+        instructions.push((code as u64, op));
+    }
+    let mut code = Vec::new();
+    for (opcode, op) in instructions {
+        let op = match opcode {
+            0 | 2 | 5 | 6 | 7 => combo(op)?,
+            3 if is_label(op) => match labels.get(op) {
+                Some(address) if *address <= 7 => *address,
+                Some(_) => return Err(ParseError::invalid(op, "jump targets must be 3-bit numbers")),
+                None => return Err(ParseError::invalid(op, "unknown label")),
+            },
+            _ => operand(op, &[])?,
+        };
+        code.extend([opcode, op]);
+    }
+    Ok(Program { registers, code })
+}
+
+/// Assemble the text, with the line and column of any error.
+pub fn assemble(text: &str) -> Result<Program, ParseError> {
+    assemble_lines(text).map_err(|e| e.locate(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assemble, Program};
+    use crate::day17::disasm::disassemble;
+    use crate::day17::{generator, EXAMPLE, EXAMPLE_2};
+    use crate::utils;
+    use std::fs;
+
+    /// The input directories whose day 17 programs round trip too, skipping
+    /// those that aren't there, as the benchmarks do
+    const INPUT_DIRS: [&str; 2] = ["input", "owen"];
+
+    #[test]
+    fn test_round_trip() {
+        let inputs: Vec<String> = INPUT_DIRS.iter()
+            .filter_map(|dir| fs::read_to_string(utils::input_path(dir, "day17")).ok())
+            .collect();
+        let programs = [EXAMPLE, EXAMPLE_2, "Register A: 1\nRegister B: 0\nRegister C: 0\n\
+                                             \nProgram: 3,7,4,3,1,7,3,2"];
+        for input in programs.into_iter().chain(inputs.iter().map(String::as_str)) {
+            let d = generator(input).unwrap();
+            let program = assemble(&disassemble(&d)).unwrap();
+            assert_eq!(d.to_instructions(), program.code, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_assemble() {
+        let program = assemble("a = 729
+; the first example
+start: adv 1
+    OUT a   # upper case is fine too
+    bxc
+    jnz start
+").unwrap();
+        assert_eq!(Program { registers: Some([729, 0, 0]), code: vec![0, 1, 5, 4, 4, 0, 3, 0] },
+                   program);
+        let input = program.to_input();
+        assert_eq!("Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,4,0,3,0\n",
+                   input);
+        assert_eq!(generator(&input).unwrap().to_instructions(), program.code);
+        assert_eq!("Program: 2,6,3,6\n", assemble("bst c\nend:\njnz 6").unwrap().to_input());
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |text: &str| assemble(text).unwrap_err().to_string();
+        assert_eq!("line 2 column 5: combo operand 7 is reserved", error("adv 1\nout 7"));
+        assert_eq!("line 1 column 5: operands must be 3-bit numbers", error("bxl 8"));
+        assert_eq!("line 1 column 1: unknown instruction", error("mov a"));
+        assert_eq!("line 1 column 6: missing operand", error("  out"));
+        assert_eq!("line 1 column 5: unknown label", error("jnz top"));
+        assert_eq!("line 2 column 1: the label is already defined", error("x: out a\nx: out b"));
+        assert_eq!("line 6 column 5: jump targets must be 3-bit numbers",
+                   error("out a\nout a\nout a\nout a\nend:\njnz end"));
+        assert_eq!("line 1 column 1: registers are a, b and c", error("d = 1"));
+        assert_eq!("line 1 column 9: unexpected character 'b'", error("out a   b"));
+    }
+}
@@ -0,0 +1,182 @@
+//! Turn a day 17 program back into something readable: a listing with one
+//! mnemonic per instruction, and pseudo-code for the usual shape of a single
+//! loop that shifts A until it's zero.
+
+use crate::day17::Debugger;
+use crate::day17::Instruction::{self, ADV, BDV, BST, BXC, BXL, CDV, JNZ, OUT};
+use std::collections::BTreeSet;
+
+/// The mnemonics of the instructions, indexed by opcode.
+pub const MNEMONICS: [&str; 8] = ["adv", "bxl", "bst", "jnz", "bxc", "out", "bdv", "cdv"];
+
+/// The registers that a combo operand of 4 to 6 reads.
+const REGISTERS: [&str; 3] = ["a", "b", "c"];
+
+/// The text of a combo operand: a literal for 0 to 3, otherwise a register.
+fn combo(op: u64) -> String {
+    match op {
+        0..=3 => op.to_string(),
+        _ => REGISTERS[op as usize - 4].to_string(),
+    }
+}
+
+/// The label of the instruction at an address.
+fn label(address: u64) -> String {
+    format!("L{address}")
+}
+
+/// Where a jump goes in a program of len instructions: the label of an
+/// instruction, or the bare address for one that's odd or past the end.
+fn target(op: u64, len: usize) -> String {
+    if op.is_multiple_of(2) && op < 2 * len as u64 { label(op) } else { op.to_string() }
+}
+
+/// The instruction as assembly in a program of len instructions, with its
+/// operand resolved.
+pub fn mnemonic(instruction: &Instruction, len: usize) -> String {
+    let [code, op] = instruction.encode();
+    let operand = match instruction {
+        ADV(_) | BST(_) | OUT(_) | BDV(_) | CDV(_) => combo(op),
+        JNZ(_) => target(op, len),
+        // The operand of bxc is read but ignored
+        BXL(_) | BXC(_) => op.to_string(),
+    };
+    format!("{} {operand}", MNEMONICS[code as usize])
+}
+
+/// What the instruction does in a program of len instructions, as a
+/// statement of pseudo-code. Output is taken modulo 8, which out_masked
+/// leaves out when the value is known to fit in 3 bits already.
+pub fn statement(instruction: &Instruction, len: usize, out_masked: bool) -> String {
+    match *instruction {
+        ADV(op) => format!("a >>= {}", combo(op)),
+        BXL(op) => format!("b ^= {op}"),
+        BST(op) if op < 4 => format!("b = {op}"),
+        BST(op) => format!("b = {} % 8", combo(op)),
+        JNZ(op) => format!("if a != 0 goto {}", target(op, len)),
+        BXC(_) => "b ^= c".to_string(),
+        OUT(op) if op < 4 || !out_masked => format!("out({})", combo(op)),
+        OUT(op) => format!("out({} % 8)", combo(op)),
+        BDV(op) => format!("b = a >> {}", combo(op)),
+        CDV(op) => format!("c = a >> {}", combo(op)),
+    }
+}
+
+/// A listing of the program with a line per instruction, each with its
+/// address and what it does, and a label on each instruction that's jumped
+/// to. The listing can be assembled back into the same program.
+pub fn disassemble(d: &Debugger) -> String {
+    let len = d.program.len();
+    let targets: BTreeSet<u64> = d.program.iter()
+        .filter_map(|i| match i {
+            JNZ(op) => Some(*op),
+            _ => None,
+        })
+        .collect();
+    let mut out = String::new();
+    for (i, instruction) in d.program.iter().enumerate() {
+        let address = 2 * i as u64;
+        if targets.contains(&address) {
+            out += &format!("{}:\n", label(address));
+        }
+        out += &format!("    {:<10}; {address:>2}: {}\n", mnemonic(instruction, len),
+                        statement(instruction, len, true));
+    }
+    out
+}
+
+/// Pseudo-code for a program that runs straight through, or that loops
+/// back to the start with a jnz at the end until A is zero. None for any
+/// other shape, which is better read from the listing.
+pub fn decompile(d: &Debugger) -> Option<String> {
+    let jumps: Vec<usize> = d.program.iter().enumerate()
+        .filter(|(_, i)| matches!(i, JNZ(_)))
+        .map(|(n, _)| n)
+        .collect();
+    let (body, looping) = match jumps[..] {
+        [] => (&d.program[..], false),
+        [n] if n == d.program.len() - 1 && d.program[n] == JNZ(0) => (&d.program[..n], true),
+        _ => return None,
+    };
+    // Which of A, B and C are known to fit in 3 bits, so that out needs no mask
+    let mut small = [false; 3];
+    let indent = if looping { "    " } else { "" };
+    let mut out = String::from(if looping { "do {\n" } else { "" });
+    for instruction in body {
+        let masked = match *instruction {
+            OUT(op) if op >= 4 => !small[op as usize - 4],
+            _ => true,
+        };
+        out += &format!("{indent}{}\n", statement(instruction, d.program.len(), masked));
+        match *instruction {
+            ADV(_) => small[0] = false,
+            BST(_) => small[1] = true,
+            BXC(_) => small[1] &= small[2],
+            BDV(_) => small[1] = false,
+            CDV(_) => small[2] = false,
+            _ => {}
+        }
+    }
+    if looping {
+        out += "} while a != 0\n";
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decompile, disassemble};
+    use crate::day17::{generator, EXAMPLE, EXAMPLE_2};
+
+    const INPUT: &str = "Register A: 44374556
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0";
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(disassemble(&generator(INPUT).unwrap()), "L0:
+    bst a     ;  0: b = a % 8
+    bxl 5     ;  2: b ^= 5
+    cdv b     ;  4: c = a >> b
+    bxl 6     ;  6: b ^= 6
+    adv 3     ;  8: a >>= 3
+    bxc 1     ; 10: b ^= c
+    out b     ; 12: out(b % 8)
+    jnz L0    ; 14: if a != 0 goto L0
+");
+        assert_eq!(disassemble(&generator(EXAMPLE).unwrap()), "L0:
+    adv 1     ;  0: a >>= 1
+    out a     ;  2: out(a % 8)
+    jnz L0    ;  4: if a != 0 goto L0
+");
+        let d = generator("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,7,5,2")
+            .unwrap();
+        assert_eq!(disassemble(&d), "    jnz 7     ;  0: if a != 0 goto 7
+    out 2     ;  2: out(2)
+");
+    }
+
+    #[test]
+    fn test_decompile() {
+        assert_eq!(decompile(&generator(INPUT).unwrap()).unwrap(), "do {
+    b = a % 8
+    b ^= 5
+    c = a >> b
+    b ^= 6
+    a >>= 3
+    b ^= c
+    out(b % 8)
+} while a != 0
+");
+        assert_eq!(decompile(&generator(EXAMPLE_2).unwrap()).unwrap(),
+                   "do {\n    a >>= 3\n    out(a % 8)\n} while a != 0\n");
+        let d = generator("Register A: 9\nRegister B: 0\nRegister C: 0\n\nProgram: 2,6,1,3,5,5")
+            .unwrap();
+        assert_eq!(decompile(&d).unwrap(), "b = c % 8\nb ^= 3\nout(b)\n");
+        let d = generator("Register A: 9\nRegister B: 0\nRegister C: 0\n\nProgram: 3,4,5,5,3,0")
+            .unwrap();
+        assert_eq!(None, decompile(&d));
+    }
+}
@@ -0,0 +1,492 @@
+//! An interactive debugger for day 17 programs that reads commands line by
+//! line and writes what happened, so that a session can be scripted as well
+//! as typed. Every step is recorded, so it can also go backwards.
+
+use crate::day17::disasm::{mnemonic, statement, MNEMONICS};
+use crate::day17::{Debugger, Instruction, State};
+use std::io::{self, BufRead, Write};
+
+/// How many instructions continue runs before giving up on a program that
+/// doesn't reach a breakpoint or halt.
+const MAX_STEPS: usize = 1_000_000;
+
+const HELP: &str = "\
+step [n]         run n instructions (s, default 1)
+next             run until the next output (n)
+continue         run until a breakpoint or the end (c)
+back [n]         undo the last n instructions or sets (default 1)
+break [where]    stop at an address or before an instruction such as out,
+                 or list the breakpoints
+delete <where>   remove a breakpoint
+watch <expr>     show an expression such as a % 8 or (a >> 3) ^ b at each stop
+unwatch <n>      remove the nth watch
+set <reg> <n>    set register a, b or c
+restart [a]      start again from the input's registers, with A set to a
+list             list the program with the next instruction marked
+regs             show the registers and output
+quit             leave (q)";
+
+/// Where the debugger stops: before the instruction at an address, or
+/// before any instruction with an opcode.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Breakpoint {
+    Address(u64),
+    Opcode(u64),
+}
+
+impl Breakpoint {
+    fn parse(text: &str) -> Result<Self, String> {
+        match MNEMONICS.iter().position(|m| *m == text) {
+            Some(code) => Ok(Breakpoint::Opcode(code as u64)),
+            None => match text.parse::<u64>() {
+                Ok(address) if address.is_multiple_of(2) => Ok(Breakpoint::Address(address)),
+                Ok(address) => Err(format!("instructions are at even addresses, not {address}")),
+                Err(_) => Err(format!("expected an address or an instruction, not '{text}'")),
+            },
+        }
+    }
+}
+
+/// A binary operator of a watch expression.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    Or,
+    Xor,
+    And,
+    Shl,
+    Shr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl Op {
+    const ALL: [(&'static str, Op); 10] = [
+        ("<<", Op::Shl), (">>", Op::Shr), ("|", Op::Or), ("^", Op::Xor), ("&", Op::And),
+        ("+", Op::Add), ("-", Op::Sub), ("*", Op::Mul), ("/", Op::Div), ("%", Op::Rem),
+    ];
+
+    /// How tightly the operator binds, as in C.
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Or => 0,
+            Op::Xor => 1,
+            Op::And => 2,
+            Op::Shl | Op::Shr => 3,
+            Op::Add | Op::Sub => 4,
+            Op::Mul | Op::Div | Op::Rem => 5,
+        }
+    }
+
+    fn apply(self, x: u64, y: u64) -> Result<u64, String> {
+        let shift = |y: u64| u32::try_from(y).ok().filter(|y| *y < 64);
+        Ok(match self {
+            Op::Or => x | y,
+            Op::Xor => x ^ y,
+            Op::And => x & y,
+            Op::Shl => shift(y).map_or(0, |y| x << y),
+            Op::Shr => shift(y).map_or(0, |y| x >> y),
+            Op::Add => x.wrapping_add(y),
+            Op::Sub => x.wrapping_sub(y),
+            Op::Mul => x.wrapping_mul(y),
+            Op::Div => x.checked_div(y).ok_or("division by zero")?,
+            Op::Rem => x.checked_rem(y).ok_or("division by zero")?,
+        })
+    }
+}
+
+/// An expression over the registers and the instruction pointer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Expr {
+    Number(u64),
+    Register(char),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn parse(text: &str) -> Result<Self, String> {
+        let tokens = tokenize(text)?;
+        let mut pos = 0;
+        let expr = Self::parse_binary(&tokens, &mut pos, 0)?;
+        match tokens.get(pos) {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected '{token}'")),
+        }
+    }
+
+    /// Parse operands joined by operators that bind at least as tightly as
+    /// min, by precedence climbing.
+    fn parse_binary(tokens: &[String], pos: &mut usize, min: u8) -> Result<Self, String> {
+        let mut left = Self::parse_operand(tokens, pos)?;
+        while let Some(op) = tokens.get(*pos).and_then(|t| Op::ALL.iter().find(|(s, _)| s == t)) {
+            let op = op.1;
+            if op.precedence() < min {
+                break;
+            }
+            *pos += 1;
+            let right = Self::parse_binary(tokens, pos, op.precedence() + 1)?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_operand(tokens: &[String], pos: &mut usize) -> Result<Self, String> {
+        let token = tokens.get(*pos).ok_or("missing operand")?;
+        *pos += 1;
+        match token.as_str() {
+            "(" => {
+                let expr = Self::parse_binary(tokens, pos, 0)?;
+                if tokens.get(*pos).map(String::as_str) != Some(")") {
+                    return Err("missing ')'".to_string());
+                }
+                *pos += 1;
+                Ok(expr)
+            }
+            "a" | "b" | "c" | "ip" => Ok(Expr::Register(token.chars().next().unwrap())),
+            _ => token.parse().map(Expr::Number).map_err(|_| format!("unexpected '{token}'")),
+        }
+    }
+
+    fn eval(&self, state: &State) -> Result<u64, String> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Register('a') => Ok(state.a),
+            Expr::Register('b') => Ok(state.b),
+            Expr::Register('c') => Ok(state.c),
+            Expr::Register(_) => Ok(2 * state.ip),
+            Expr::Binary(op, x, y) => op.apply(x.eval(state)?, y.eval(state)?),
+        }
+    }
+}
+
+/// Split an expression into numbers, names, operators and parentheses.
+fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_alphanumeric() {
+            rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len())
+        } else if rest.starts_with("<<") || rest.starts_with(">>") {
+            2
+        } else if "()|^&+-*/%".contains(c) {
+            1
+        } else {
+            return Err(format!("unexpected '{c}'"));
+        };
+        tokens.push(rest[..len].to_string());
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// The state of a debugging session.
+struct Session {
+    program: Vec<Instruction>,
+    initial: State,
+    state: State,
+    output: Vec<u64>,
+    /// The state and the length of the output before each step or set,
+    /// latest last
+    trace: Vec<(State, usize)>,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<(String, Expr)>,
+}
+
+impl Session {
+    fn new(d: &Debugger) -> Self {
+        Session {
+            program: d.program.clone(),
+            initial: d.state.clone(),
+            state: d.state.clone(),
+            output: Vec::new(),
+            trace: Vec::new(),
+            breakpoints: Vec::new(),
+            watches: Vec::new(),
+        }
+    }
+
+    fn halted(&self) -> bool {
+        self.state.ip >= self.program.len() as u64
+    }
+
+    /// Run one instruction, returning whether it output anything.
+    fn step(&mut self) -> bool {
+        self.trace.push((self.state.clone(), self.output.len()));
+        match self.program[self.state.ip as usize].perform(&mut self.state) {
+            Some(o) => {
+                self.output.push(o);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn at_breakpoint(&self) -> bool {
+        !self.halted() && self.breakpoints.iter().any(|b| match *b {
+            Breakpoint::Address(address) => address / 2 == self.state.ip,
+            Breakpoint::Opcode(code) => self.program[self.state.ip as usize].encode()[0] == code,
+        })
+    }
+
+    /// Run until the program halts, reaches a breakpoint, or outputs when
+    /// to_output is set.
+    fn run(&mut self, to_output: bool, out: &mut dyn Write) -> io::Result<()> {
+        for _ in 0..MAX_STEPS {
+            if self.halted() {
+                return Ok(());
+            }
+            if self.step() && to_output {
+                return Ok(());
+            }
+            if self.at_breakpoint() {
+                return writeln!(out, "Breakpoint at {}", 2 * self.state.ip);
+            }
+        }
+        writeln!(out, "Stopped after {MAX_STEPS} instructions")
+    }
+
+    /// Show where the program is, the registers, the output and the watches.
+    fn show(&self, out: &mut dyn Write) -> io::Result<()> {
+        match self.program.get(self.state.ip as usize) {
+            Some(i) => writeln!(out, "{:>2}: {:<10}; {}", 2 * self.state.ip,
+                                mnemonic(i, self.program.len()),
+                                statement(i, self.program.len(), true))?,
+            None => writeln!(out, "Halted")?,
+        }
+        let output: Vec<String> = self.output.iter().map(u64::to_string).collect();
+        writeln!(out, "    a = {}, b = {}, c = {}, out = [{}]", self.state.a, self.state.b,
+                 self.state.c, output.join(","))?;
+        for (n, (text, expr)) in self.watches.iter().enumerate() {
+            match expr.eval(&self.state) {
+                Ok(value) => writeln!(out, "    {}: {text} = {value}", n + 1)?,
+                Err(e) => writeln!(out, "    {}: {text}: {e}", n + 1)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn list(&self, out: &mut dyn Write) -> io::Result<()> {
+        for (n, i) in self.program.iter().enumerate() {
+            let marker = if n as u64 == self.state.ip { "=>" } else { "  " };
+            writeln!(out, "{marker} {:>2}: {:<10}; {}", 2 * n, mnemonic(i, self.program.len()),
+                     statement(i, self.program.len(), true))?;
+        }
+        Ok(())
+    }
+
+    /// Carry out one command, returning false when the session should end.
+    /// A command that changes the state shows the new one.
+    fn command(&mut self, line: &str, out: &mut dyn Write) -> io::Result<bool> {
+        let (name, args) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let args = args.trim();
+        let count = || if args.is_empty() { Ok(1) } else { args.parse::<usize>() };
+        match name {
+            "" => return Ok(true),
+            "quit" | "q" => return Ok(false),
+            "help" | "h" => writeln!(out, "{HELP}")?,
+            "step" | "s" => match count() {
+                Ok(n) => {
+                    for _ in 0..n {
+                        if self.halted() {
+                            break;
+                        }
+                        self.step();
+                    }
+                    self.show(out)?;
+                }
+                Err(_) => writeln!(out, "expected a number of steps, not '{args}'")?,
+            },
+            "next" | "n" => {
+                self.run(true, out)?;
+                self.show(out)?;
+            }
+            "continue" | "c" => {
+                self.run(false, out)?;
+                self.show(out)?;
+            }
+            "back" => match count() {
+                Ok(n) => {
+                    let n = n.min(self.trace.len());
+                    if let Some((state, len)) = self.trace.drain(self.trace.len() - n..).next() {
+                        self.state = state;
+                        self.output.truncate(len);
+                    }
+                    self.show(out)?;
+                }
+                Err(_) => writeln!(out, "expected a number of steps, not '{args}'")?,
+            },
+            "break" | "b" if args.is_empty() => {
+                for b in &self.breakpoints {
+                    match b {
+                        Breakpoint::Address(address) => writeln!(out, "at {address}")?,
+                        Breakpoint::Opcode(code) => writeln!(out, "on {}", MNEMONICS[*code as usize])?,
+                    }
+                }
+            }
+            "break" | "b" => match Breakpoint::parse(args) {
+                Ok(b) if !self.breakpoints.contains(&b) => self.breakpoints.push(b),
+                Ok(_) => {}
+                Err(e) => writeln!(out, "{e}")?,
+            },
+            "delete" => match Breakpoint::parse(args) {
+                Ok(b) => self.breakpoints.retain(|other| *other != b),
+                Err(e) => writeln!(out, "{e}")?,
+            },
+            "watch" => match Expr::parse(args) {
+                Ok(expr) => {
+                    self.watches.push((args.to_string(), expr));
+                    self.show(out)?;
+                }
+                Err(e) => writeln!(out, "invalid expression '{args}': {e}")?,
+            },
+            "unwatch" => match args.parse::<usize>() {
+                Ok(n) if n >= 1 && n <= self.watches.len() => {
+                    self.watches.remove(n - 1);
+                }
+                _ => writeln!(out, "there is no watch '{args}'")?,
+            },
+            "set" => {
+                let register = args.split_once(' ')
+                    .and_then(|(r, v)| Some((r, v.trim().parse::<u64>().ok()?)));
+                let mut state = self.state.clone();
+                match register {
+                    Some(("a", v)) => state.a = v,
+                    Some(("b", v)) => state.b = v,
+                    Some(("c", v)) => state.c = v,
+                    _ => {
+                        writeln!(out, "expected set a, b or c and a value")?;
+                        return Ok(true);
+                    }
+                }
+                // Recorded like a step, so that back undoes it
+                self.trace.push((std::mem::replace(&mut self.state, state), self.output.len()));
+                self.show(out)?;
+            }
+            "restart" => {
+                let a = if args.is_empty() { Ok(self.initial.a) } else { args.parse::<u64>() };
+                match a {
+                    Ok(a) => {
+                        self.state = State { a, ..self.initial.clone() };
+                        self.output.clear();
+                        self.trace.clear();
+                        self.show(out)?;
+                    }
+                    Err(_) => writeln!(out, "expected a value for A, not '{args}'")?,
+                }
+            }
+            "list" | "l" => self.list(out)?,
+            "regs" | "r" => self.show(out)?,
+            _ => writeln!(out, "unknown command '{name}', try help")?,
+        }
+        Ok(true)
+    }
+}
+
+/// Debug the program, reading commands from input until it ends or a quit,
+/// and writing a prompt and the results of each command to out.
+pub fn debug(d: &Debugger, input: impl BufRead, out: &mut dyn Write) -> io::Result<()> {
+    let mut session = Session::new(d);
+    session.show(out)?;
+    write!(out, "(day17) ")?;
+    out.flush()?;
+    for line in input.lines() {
+        if !session.command(&line?, out)? {
+            return Ok(());
+        }
+        write!(out, "(day17) ")?;
+        out.flush()?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{debug, Expr};
+    use crate::day17::{generator, State};
+
+    const INPUT: &str = "Register A: 44374556
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0";
+
+    /// Run a script of commands and return what the debugger wrote,
+    /// without the prompts.
+    fn script(commands: &str) -> String {
+        let mut out = Vec::new();
+        debug(&generator(INPUT).unwrap(), commands.as_bytes(), &mut out).unwrap();
+        String::from_utf8(out).unwrap().replace("(day17) ", "")
+    }
+
+    #[test]
+    fn test_expr() {
+        let state = State { a: 44374556, b: 5, c: 2, ip: 3 };
+        let eval = |text: &str| Expr::parse(text).and_then(|e| e.eval(&state));
+        assert_eq!(Ok(4), eval("a % 8"));
+        assert_eq!(Ok(7), eval("b ^ c"));
+        assert_eq!(Ok(5 ^ (2 & 3)), eval("b ^ c & 3"));
+        assert_eq!(Ok(((44374556 >> 3) % 8) ^ 5), eval("(a >> 3) % 8 ^ b"));
+        assert_eq!(Ok(6), eval("ip"));
+        assert_eq!(Err("division by zero".to_string()), eval("a / (b - 5)"));
+        assert!(Expr::parse("a +").is_err());
+        assert!(Expr::parse("(a").is_err());
+        assert!(Expr::parse("d").is_err());
+    }
+
+    #[test]
+    fn test_step_and_back() {
+        assert_eq!(script("step 2\nback\nq\n"), " 0: bst a     ; b = a % 8
+    a = 44374556, b = 0, c = 0, out = []
+ 4: cdv b     ; c = a >> b
+    a = 44374556, b = 1, c = 0, out = []
+ 2: bxl 5     ; b ^= 5
+    a = 44374556, b = 4, c = 0, out = []
+");
+        // Going back past an output takes it back too
+        let out = script("next\nback 7\nq");
+        assert!(out.contains("14: jnz L0    ; if a != 0 goto L0\n    \
+                              a = 5546819, b = 22187273, c = 22187278, out = [1]\n"), "{out}");
+        assert!(out.ends_with(" 0: bst a     ; b = a % 8\n    a = 44374556, b = 0, c = 0, out = []\n"),
+                "{out}");
+    }
+
+    #[test]
+    fn test_breakpoints() {
+        let out = script("break 14\ncontinue\ncontinue\ndelete 14\nbreak out\nc\nbreak\nq");
+        let stops: Vec<&str> = out.lines().filter(|l| l.starts_with("Breakpoint")).collect();
+        assert_eq!(vec!["Breakpoint at 14", "Breakpoint at 14", "Breakpoint at 12"], stops);
+        assert!(out.contains("Breakpoint at 12\n12: out b     ; out(b % 8)\n    \
+                              a = 86669, b = 21664, c = 21667, out = [1,5]\non out\n"), "{out}");
+        let out = script("c\nq");
+        assert!(out.contains("Halted\n"), "{out}");
+        assert!(out.ends_with("out = [1,5,0,3,7,3,0,3,1]\n"), "{out}");
+        assert!(script("break 3x\n").contains("expected an address or an instruction, not '3x'"));
+        // Nothing was added, so listing the breakpoints shows none
+        assert!(script("break 3\nbreak\n")
+            .ends_with("instructions are at even addresses, not 3\n\n"));
+    }
+
+    #[test]
+    fn test_watch_set_and_restart() {
+        let out = script("watch a % 8\nset a 117440\nrestart 7\nbogus\nunwatch 2\n");
+        assert!(out.contains("    a = 44374556, b = 0, c = 0, out = []\n    1: a % 8 = 4\n"), "{out}");
+        assert!(out.contains("    a = 117440, b = 0, c = 0, out = []\n    1: a % 8 = 0\n"), "{out}");
+        assert!(out.contains("    a = 7, b = 0, c = 0, out = []\n    1: a % 8 = 7\n"), "{out}");
+        assert!(out.contains("unknown command 'bogus', try help\n"));
+        assert!(out.contains("there is no watch '2'\n"));
+        let out = script("restart 7\nc");
+        assert!(out.contains("Halted\n    a = 0, b = 5, c = 1, out = [5]\n"), "{out}");
+        // A set is undone by back like a step, in the order they happened
+        let out = script("step\nset b 3\nstep\nback\nback\nback\n");
+        assert!(out.ends_with(" 2: bxl 5     ; b ^= 5
+    a = 44374556, b = 3, c = 0, out = []
+ 2: bxl 5     ; b ^= 5
+    a = 44374556, b = 4, c = 0, out = []
+ 0: bst a     ; b = a % 8
+    a = 44374556, b = 0, c = 0, out = []
+
+"), "{out}");
+    }
+}
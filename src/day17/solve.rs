@@ -0,0 +1,252 @@
+//! Find the smallest value of register A that makes a program output a given
+//! sequence, such as the program itself for part 2.
+//!
+//! A program that loops until A is zero while shifting A right by a fixed
+//! number of bits, k, each time around, only reads bits k * i and up of A in
+//! its i-th iteration. B and C can carry values from one iteration to the
+//! next though, so the outputs of iteration i can also depend on the bits of
+//! the iterations before it, up to some lag. With that lag known, the last
+//! outputs depend only on the highest bits, and A can be built k bits at a
+//! time from the top, keeping each candidate whose outputs from lag
+//! iterations later on are right. Any other program, including one whose B
+//! or C carries bits of A through every iteration, is searched by trying
+//! every small value of A in turn.
+
+use crate::day17::Debugger;
+use crate::day17::Instruction::{self, ADV, BDV, BST, BXC, CDV, JNZ, OUT};
+
+/// The most instructions a single run may take, so that a program which
+/// never halts for some A is given up on.
+const MAX_STEPS: usize = 10_000;
+
+/// The values of A below 2 to this power are tried for programs that the
+/// search by iterations doesn't apply to.
+const BRUTE_FORCE_BITS: u32 = 16;
+
+/// The registers as bits of a set, for what each register depends on.
+const A: u8 = 1;
+const B: u8 = 2;
+const C: u8 = 4;
+
+/// Run the program with A set to a, stopping once it has output more than
+/// limit values. None if it didn't halt within MAX_STEPS.
+fn run(d: &Debugger, a: u64, limit: usize) -> Option<Vec<u64>> {
+    let mut state = d.state.clone();
+    state.a = a;
+    let mut output = Vec::new();
+    for _ in 0..MAX_STEPS {
+        let Some(instruction) = d.program.get(state.ip as usize) else { return Some(output) };
+        if let Some(o) = instruction.perform(&mut state) {
+            output.push(o);
+            if output.len() > limit {
+                return Some(output);
+            }
+        }
+    }
+    None
+}
+
+/// The shape of a program that is a single loop over A.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Loop {
+    /// The bits that A is shifted right by in each iteration
+    shift: u32,
+    /// The number of values output in each iteration
+    outputs: usize,
+    /// How many iterations back the outputs of an iteration can depend on
+    /// the bits of A, through the values that B and C carry over
+    lag: usize,
+}
+
+/// The registers that the values a loop body outputs depend on, and those
+/// that A, B and C depend on at its end, as sets of the registers at its
+/// start.
+fn dependencies(body: &[Instruction]) -> (u8, [u8; 3]) {
+    let combo = |registers: &[u8; 3], op: u64| match op {
+        4..=6 => registers[op as usize - 4],
+        _ => 0,
+    };
+    let mut registers = [A, B, C];
+    let mut output = 0;
+    for instruction in body {
+        match *instruction {
+            ADV(op) => registers[0] |= combo(&registers, op),
+            BST(op) => registers[1] = combo(&registers, op),
+            BXC(_) => registers[1] |= registers[2],
+            OUT(op) => output |= combo(&registers, op),
+            BDV(op) => registers[1] = registers[0] | combo(&registers, op),
+            CDV(op) => registers[2] = registers[0] | combo(&registers, op),
+            _ => {}
+        }
+    }
+    (output, registers)
+}
+
+/// How many iterations back the outputs of a loop body can depend on the
+/// bits of A, or None if B or C carries them through every iteration.
+fn lag(body: &[Instruction]) -> Option<usize> {
+    let (output, registers) = dependencies(body);
+    // Which of B and C at the start of an earlier iteration the outputs
+    // depend on. There are only 4 such sets, so they repeat within 4 steps
+    // back, and a dependence on A that's still there after that never ends
+    let mut carried = output & (B | C);
+    let mut lag = 0;
+    for back in 1..=8 {
+        if carried == 0 {
+            return Some(lag);
+        }
+        let depends: u8 = [B, C].iter().zip(&registers[1..])
+            .filter(|(r, _)| carried & **r != 0)
+            .map(|(_, d)| *d)
+            .fold(0, |all, d| all | d);
+        if depends & A != 0 {
+            if back > 4 {
+                return None;
+            }
+            lag = back;
+        }
+        carried = depends & (B | C);
+    }
+    Some(lag)
+}
+
+/// The shape of a program that is a single loop which jumps back to the
+/// start until A is zero and only shifts A by literal amounts.
+fn iteration(d: &Debugger) -> Option<Loop> {
+    let (last, body) = d.program.split_last()?;
+    if *last != JNZ(0) || body.iter().any(|i| matches!(i, JNZ(_))) {
+        return None;
+    }
+    let mut shift = 0;
+    for instruction in body {
+        match *instruction {
+            ADV(op) if op < 4 => shift += op as u32,
+            ADV(_) => return None,
+            _ => {}
+        }
+    }
+    let outputs = body.iter().filter(|i| matches!(i, OUT(_))).count();
+    if shift == 0 || outputs == 0 {
+        return None;
+    }
+    Some(Loop { shift, outputs, lag: lag(body)? })
+}
+
+/// Fill in the bits of A for iteration and those below it, lowest value
+/// first, where a holds the bits of the later iterations.
+fn search(d: &Debugger, target: &[u64], shape: Loop, a: u64, iteration: usize) -> Option<u64> {
+    // The outputs that the bits so far decide, whatever the lower bits are
+    let from = ((iteration + shape.lag) * shape.outputs).min(target.len());
+    let at = shape.shift * iteration as u32;
+    for v in 0..1u64 << shape.shift {
+        if (v << at) >> at != v {
+            // The bits don't fit in A
+            break;
+        }
+        let candidate = a | v << at;
+        if candidate == 0 {
+            continue;
+        }
+        let Some(output) = run(d, candidate, target.len()) else { continue };
+        if output.len() != target.len() || output[from..] != target[from..] {
+            continue;
+        }
+        if iteration == 0 {
+            return Some(candidate);
+        }
+        if let Some(found) = search(d, target, shape, candidate, iteration - 1) {
+            return Some(found);
+        }
+    }
+    None
+}
+
+/// The smallest positive value of A that makes the program output target,
+/// if there is one. For a loop that shifts A by a fixed amount, and whose B
+/// and C don't carry bits of A through every iteration, the search covers
+/// every value of A. For any other program it only covers those below
+/// 2^BRUTE_FORCE_BITS.
+pub fn solve(d: &Debugger, target: &[u64]) -> Option<u64> {
+    if let Some(shape) = iteration(d) {
+        let iterations = target.len() / shape.outputs;
+        if !target.len().is_multiple_of(shape.outputs) || iterations == 0
+            || shape.shift as usize * (iterations - 1) >= 64 {
+            return None;
+        }
+        return search(d, target, shape, 0, iterations - 1);
+    }
+    (1..1u64 << BRUTE_FORCE_BITS).find(|&a| run(d, a, target.len()).is_some_and(|o| o == target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{iteration, run, solve, Loop};
+    use crate::day17::{generator, EXAMPLE, EXAMPLE_2};
+
+    fn program(program: &str) -> crate::day17::Debugger {
+        generator(&format!("Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {program}"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_iteration() {
+        let shape = |shift, outputs, lag| Some(Loop { shift, outputs, lag });
+        assert_eq!(shape(3, 1, 0), iteration(&generator(EXAMPLE_2).unwrap()));
+        assert_eq!(shape(1, 1, 0), iteration(&generator(EXAMPLE).unwrap()));
+        assert_eq!(shape(3, 1, 0), iteration(&program("2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0")));
+        // B carries A % 8 to the next iteration, or only a value of its own
+        assert_eq!(shape(3, 1, 1), iteration(&program("5,5,2,4,0,3,3,0")));
+        assert_eq!(shape(3, 1, 0), iteration(&program("0,3,1,3,5,5,3,0")));
+        // C carries bits of A to B, which carries them on
+        assert_eq!(shape(3, 1, 2), iteration(&program("5,5,2,6,7,4,0,3,3,0")));
+        // Shifting by a register, jumping anywhere but the start, or carrying
+        // bits of A through every iteration
+        assert_eq!(None, iteration(&program("0,5,5,4,3,0")));
+        assert_eq!(None, iteration(&program("0,3,5,4,3,2")));
+        assert_eq!(None, iteration(&program("5,5,4,0,7,4,0,3,3,0")));
+    }
+
+    #[test]
+    fn test_quine() {
+        let d = program("2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0");
+        assert_eq!(Some(105981155568026), solve(&d, &d.to_instructions()));
+        // The order of B and C here used to defeat the part 2 heuristics
+        let d = program("0,3,1,3,5,4,3,0");
+        let a = solve(&d, &[0, 3, 1, 3, 5, 4, 3, 0]).unwrap();
+        assert_eq!(Some(vec![0, 3, 1, 3, 5, 4, 3, 0]), run(&d, a, 8));
+    }
+
+    #[test]
+    fn test_any_target() {
+        let d = program("2,4,1,5,7,5,1,6,0,3,4,1,5,5,3,0");
+        let target = run(&d, 0o5371, 10).unwrap();
+        let a = solve(&d, &target).unwrap();
+        assert!(a <= 0o5371);
+        assert_eq!(Some(target.clone()), run(&d, a, 10));
+        assert!((1..a).all(|smaller| run(&d, smaller, 10) != Some(target.clone())));
+        assert_eq!(None, solve(&d, &[7, 7, 7, 7]));
+        assert_eq!(None, solve(&d, &[]));
+        // Too many outputs for A to hold
+        assert_eq!(None, solve(&d, &[0; 23]));
+        // B carries bits of A from one iteration to the next
+        let d = program("5,5,2,4,0,3,3,0");
+        let target = [0, 7, 6, 5, 4, 3, 2];
+        assert_eq!(Some(target.to_vec()), run(&d, 342391, 7));
+        let a = solve(&d, &target).unwrap();
+        assert_eq!(Some(target.to_vec()), run(&d, a, 7));
+        assert!(a <= 342391);
+        let d = program("5,5,2,6,7,4,0,3,3,0");
+        let target = run(&d, 0o7654321, 7).unwrap();
+        let a = solve(&d, &target).unwrap();
+        assert_eq!(Some(target.clone()), run(&d, a, 7));
+        assert!((1..a).all(|smaller| run(&d, smaller, 7) != Some(target.clone())));
+    }
+
+    #[test]
+    fn test_brute_force() {
+        // A straight line outputs A itself, and A shifted by B never ends
+        assert_eq!(Some(5), solve(&program("5,4"), &[5]));
+        assert_eq!(Some(13), solve(&program("5,4,0,3,5,4"), &[5, 1]));
+        assert_eq!(None, solve(&program("0,5,5,4,3,0"), &[1, 2]));
+    }
+}
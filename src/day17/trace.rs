@@ -0,0 +1,202 @@
+//! A tracer that records every instruction a day 17 program executes, to
+//! see how a value of A flows through it: as JSON lines, as a table, or as
+//! a summary of the loop iterations and what each of them output.
+
+use crate::day17::disasm::mnemonic;
+use crate::day17::{Debugger, Instruction, State, Tracer};
+use serde_json::json;
+use std::fmt;
+
+/// The most instructions a trace records, so that a program which never
+/// halts doesn't fill up memory.
+pub const MAX_STEPS: usize = 100_000;
+
+/// One executed instruction.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub instruction: Instruction,
+    pub before: State,
+    pub after: State,
+    pub output: Option<u64>,
+}
+
+impl Step {
+    /// Whether the instruction jumped back, which starts another iteration
+    /// of a loop.
+    fn loops(&self) -> bool {
+        matches!(self.instruction, Instruction::JNZ(_)) && self.after.ip <= self.before.ip
+    }
+}
+
+/// The instructions executed by a run, in order.
+#[derive(Clone, Debug, Default)]
+pub struct Trace {
+    /// The length of the program, which the jump targets are shown against
+    len: usize,
+    pub steps: Vec<Step>,
+    /// Whether the program halted, rather than being stopped at MAX_STEPS
+    pub halted: bool,
+}
+
+impl Tracer for Trace {
+    fn step(&mut self, instruction: &Instruction, before: &State, after: &State,
+            output: Option<u64>) {
+        self.steps.push(Step {
+            instruction: instruction.clone(),
+            before: before.clone(),
+            after: after.clone(),
+            output,
+        });
+    }
+}
+
+/// Counts over a whole trace.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Summary {
+    pub instructions: usize,
+    /// The times through the program, counting each backwards jump as the
+    /// start of another
+    pub iterations: usize,
+    /// The number of values output by each iteration
+    pub outputs: Vec<usize>,
+    /// The value of A at the start of each iteration
+    pub a: Vec<u64>,
+    pub halted: bool,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outputs: Vec<String> = self.outputs.iter().map(usize::to_string).collect();
+        let a: Vec<String> = self.a.iter().map(u64::to_string).collect();
+        let plural = if self.iterations == 1 { "" } else { "s" };
+        writeln!(f, "Executed {} instructions in {} iteration{plural}", self.instructions,
+                 self.iterations)?;
+        writeln!(f, "Outputs per iteration: {}", outputs.join(","))?;
+        writeln!(f, "A at the start of each iteration: {}", a.join(","))?;
+        if !self.halted {
+            writeln!(f, "Stopped after {} instructions without halting", self.instructions)?;
+        }
+        Ok(())
+    }
+}
+
+impl Trace {
+    pub fn new(d: &Debugger) -> Self {
+        Trace { len: d.program.len(), steps: Vec::new(), halted: false }
+    }
+
+    /// A line of JSON per step, with the address and the registers before
+    /// and after it.
+    pub fn to_json_lines(&self) -> String {
+        let registers = |s: &State| json!({ "a": s.a, "b": s.b, "c": s.c });
+        self.steps.iter().enumerate()
+            .map(|(n, step)| json!({
+                "step": n,
+                "address": 2 * step.before.ip,
+                "instruction": mnemonic(&step.instruction, self.len),
+                "before": registers(&step.before),
+                "after": registers(&step.after),
+                "output": step.output,
+            }).to_string() + "\n")
+            .collect()
+    }
+
+    /// A line per step with the registers after it, where those that the
+    /// step changed are marked with a *.
+    pub fn to_table(&self) -> String {
+        let mut out = format!("{:>5} {:>4}  {:<10} {:>17} {:>17} {:>17}  out\n", "step", "addr",
+                              "", "a", "b", "c");
+        for (n, step) in self.steps.iter().enumerate() {
+            let (before, after) = (&step.before, &step.after);
+            let register = |old: u64, new: u64| format!("{new}{}", if old != new { "*" } else { " " });
+            out += &format!("{n:>5} {:>4}  {:<10} {:>17} {:>17} {:>17}  {}\n", 2 * before.ip,
+                            mnemonic(&step.instruction, self.len), register(before.a, after.a),
+                            register(before.b, after.b), register(before.c, after.c),
+                            step.output.map_or(String::new(), |o| o.to_string()));
+        }
+        out
+    }
+
+    pub fn summary(&self) -> Summary {
+        let mut summary = Summary { instructions: self.steps.len(), halted: self.halted,
+                                    ..Summary::default() };
+        let mut starting = true;
+        for step in &self.steps {
+            if starting {
+                summary.iterations += 1;
+                summary.outputs.push(0);
+                summary.a.push(step.before.a);
+            }
+            if step.output.is_some() {
+                *summary.outputs.last_mut().unwrap() += 1;
+            }
+            starting = step.loops();
+        }
+        summary
+    }
+}
+
+/// Run the program, with A set to a if given, and record what it did for up
+/// to MAX_STEPS instructions.
+pub fn trace(d: &Debugger, a: Option<u64>) -> Trace {
+    let mut d = d.clone();
+    if let Some(a) = a {
+        d.state.a = a;
+    }
+    let mut trace = Trace::new(&d);
+    d.run_traced(&mut trace, MAX_STEPS);
+    trace.halted = d.state.ip >= d.program.len() as u64;
+    trace
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{trace, Summary, MAX_STEPS};
+    use crate::day17::{generator, EXAMPLE};
+
+    #[test]
+    fn test_summary() {
+        let d = generator(EXAMPLE).unwrap();
+        let summary = trace(&d, None).summary();
+        assert_eq!(Summary {
+            instructions: 30,
+            iterations: 10,
+            outputs: vec![1; 10],
+            a: vec![729, 364, 182, 91, 45, 22, 11, 5, 2, 1],
+            halted: true,
+        }, summary);
+        assert_eq!("Executed 30 instructions in 10 iterations
+Outputs per iteration: 1,1,1,1,1,1,1,1,1,1
+A at the start of each iteration: 729,364,182,91,45,22,11,5,2,1
+", summary.to_string());
+        let summary = trace(&d, Some(0)).summary();
+        assert_eq!((3, 1, vec![1]), (summary.instructions, summary.iterations, summary.outputs));
+        // A jump forwards isn't another iteration
+        let d = generator("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,4,5,4,5,4")
+            .unwrap();
+        assert_eq!(vec![1], trace(&d, None).summary().outputs);
+        // A program that never halts is stopped
+        let d = generator("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0").unwrap();
+        let summary = trace(&d, None).summary();
+        assert_eq!((MAX_STEPS, false), (summary.instructions, summary.halted));
+        assert!(summary.to_string()
+            .ends_with(&format!("Stopped after {MAX_STEPS} instructions without halting\n")));
+    }
+
+    #[test]
+    fn test_formats() {
+        let d = generator(EXAMPLE).unwrap();
+        let trace = trace(&d, Some(2));
+        assert_eq!("{\"address\":0,\"after\":{\"a\":1,\"b\":0,\"c\":0},\
+\"before\":{\"a\":2,\"b\":0,\"c\":0},\"instruction\":\"adv 1\",\"output\":null,\"step\":0}",
+                   trace.to_json_lines().lines().next().unwrap());
+        assert_eq!(6, trace.to_json_lines().lines().count());
+        let table = trace.to_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(7, lines.len());
+        assert_eq!("    0    0  adv 1                     1*                0                 0   ",
+                   lines[1]);
+        assert_eq!("    1    2  out a                     1                 0                 0   1",
+                   lines[2]);
+    }
+}
@@ -1,108 +1,126 @@
-use std::collections::VecDeque;
+use crate::day::{Day, Example, ParseError};
+use crate::params::{Param, Params};
+use crate::utils::grid::{Grid, Point};
+use crate::utils::search::{self, Graph};
 
-const NEIGHBORS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const SIZE: i32 = 70;
+const BYTES_FALLEN: u32 = 1024;
+const MAX_SIZE: i64 = 1000;
 
-pub fn part1(m: &Memory) -> u32 {
-    m.walk(1024).unwrap()
-}
+const EXAMPLE: &str = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
 
-pub fn part2(m: &Memory) -> String {
-    let result = m.blocking_byte(0, m.max_byte as usize);
-    format!("{},{}", result.x, result.y)
-}
+pub struct Solution;
 
-pub fn generator(input: &str) -> Memory {
-    Memory::new(input, 70, 70)
-}
+impl Day for Solution {
+    type Input = Memory;
+    type Output1 = u32;
+    type Output2 = String;
 
-#[derive(Debug, Eq, PartialEq, Default, Clone)]
-struct XY {
-    x: i32,
-    y: i32,
-}
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("22"), Some("6,1")],
+                  params: &[("size", 6), ("bytes", 12)] },
+    ];
 
-impl XY {
-    fn new(input: &str) -> XY {
-        let (x, y) = input.split_once(",").unwrap();
-        XY {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        }
+    const PARAMS: &'static [Param] = &[
+        ("size", SIZE as i64, 0..=MAX_SIZE),
+        ("bytes", BYTES_FALLEN as i64, 0..=u32::MAX as i64),
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn generator_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        let size = params.get("size") as i32;
+        Memory::new(input, size, size)
     }
 
-    fn apply(&mut self, dx: &i32, dy: &i32) {
-        self.x += dx;
-        self.y += dy;
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part1_with(input: &Self::Input, params: &Params) -> u32 {
+        input.walk(params.get("bytes") as u32).unwrap()
+    }
+
+    fn part2(input: &Self::Input) -> String {
+        part2(input)
     }
 }
 
+pub fn part1(m: &Memory) -> u32 {
+    m.walk(BYTES_FALLEN).unwrap()
+}
+
+pub fn part2(m: &Memory) -> String {
+    m.blocking_byte(0, m.max_byte as usize).to_string()
+}
+
+pub fn generator(input: &str) -> Result<Memory, ParseError> {
+    Memory::new(input, SIZE, SIZE)
+}
+
 #[derive(Debug)]
 pub struct Memory {
     max_byte: u32,
-    bytes: Vec<Vec<u32>>,
-    start: XY,
-    exit: XY,
+    bytes: Grid<u32>,
+    start: Point,
+    exit: Point,
 }
 
 impl Memory {
-    pub fn new(input: &str, dest_x: i32, dest_y: i32) -> Memory {
-        let mut bytes = vec![vec![u32::MAX; dest_x as usize + 1]; dest_y as usize + 1];
+    pub fn new(input: &str, dest_x: i32, dest_y: i32) -> Result<Memory, ParseError> {
+        let mut bytes = Grid::new(dest_x as usize + 1, dest_y as usize + 1, u32::MAX);
         let mut max_byte = 0;
-        input.lines().enumerate().for_each(|(y, line)| {
-            let p = XY::new(line);
-            bytes[p.y as usize][p.x as usize] = (y + 1) as u32;
+        for (y, line) in input.lines().enumerate() {
+            let p = Point::parse(line)?;
+            if !bytes.contains(p) {
+                return Err(ParseError::invalid(line, "byte is outside of the memory space"));
+            }
+            bytes[p] = (y + 1) as u32;
             max_byte += 1;
-        });
+        }
 
-        Memory {
+        Ok(Memory {
             max_byte,
             bytes,
-            start: XY::default(),
-            exit: XY { x: dest_x, y: dest_y },
-        }
+            start: Point::default(),
+            exit: Point::new(dest_x, dest_y),
+        })
     }
 
     fn walk(&self, after: u32) -> Option<u32> {
-        let mut min_steps = vec![vec![u32::MAX; self.exit.x as usize + 1]; self.exit.y as usize + 1];
-        let mut queue = VecDeque::new();
-        min_steps[self.start.y as usize][self.start.x as usize] = 0;
-        queue.push_back(self.start.clone());
-
-        while !queue.is_empty() {
-            let b = queue.pop_front().unwrap();
-            if b == self.exit {
-                return Some(min_steps[b.y as usize][b.x as usize]);
-            }
-
-            for (dx, dy) in NEIGHBORS.iter() {
-                let mut next = b.clone();
-                next.apply(dx, dy);
-                if self.is_valid(&next)
-                    && self.bytes[next.y as usize][next.x as usize] > after
-                    && min_steps[next.y as usize][next.x as usize] > min_steps[b.y as usize][b.x as usize] + 1 {
-                    min_steps[next.y as usize][next.x as usize] = min_steps[b.y as usize][b.x as usize] + 1;
-                    queue.push_back(next);
-                }
-            }
-        }
-        None
+        let fallen = Fallen { memory: self, after };
+        search::bfs_distances(&fallen, self.start, |p| *p == self.exit).goal_distance()
     }
 
-    fn is_valid(&self, b: &XY) -> bool {
-        b.x >= self.start.x && b.x <= self.exit.x
-            && b.y >= self.start.y && b.y <= self.exit.y
-    }
-
-    fn blocking_byte(&self, start: usize, end: usize) -> XY {
+    fn blocking_byte(&self, start: usize, end: usize) -> Point {
         if start >= end {
-            for (y, row) in self.bytes.iter().enumerate() {
-                for (x, v) in row.iter().enumerate() {
-                    if *v == end as u32 {
-                        return XY { x: x as i32, y: y as i32 };
-                    }
-                }
-            }
-            unreachable!();
+            return self.bytes.position(|v| *v == end as u32).unwrap();
         }
 
         let mid = (start + end) / 2;
@@ -113,53 +131,49 @@ impl Memory {
     }
 }
 
+/// The memory space once some bytes have fallen, where only the cells that
+/// are still free can be walked through.
+struct Fallen<'a> {
+    memory: &'a Memory,
+    after: u32,
+}
+
+impl Graph for Fallen<'_> {
+    type Node = Point;
+    type Map<V: Clone> = Grid<Option<V>>;
+
+    fn map<V: Clone>(&self) -> Grid<Option<V>> {
+        Grid::new(self.memory.bytes.width(), self.memory.bytes.height(), None)
+    }
+
+    fn neighbors(&self, p: &Point) -> impl Iterator<Item = (Point, u32)> {
+        self.memory.bytes.neighbors(*p)
+            .filter(|n| self.memory.bytes[*n] > self.after)
+            .map(|n| (n, 1))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{Memory, XY};
-
-    const INPUT: &str = "5,4
-4,2
-4,5
-3,0
-2,1
-6,3
-2,4
-1,5
-0,6
-3,3
-2,6
-5,1
-1,2
-5,5
-2,5
-6,5
-1,4
-0,4
-6,4
-1,1
-6,1
-1,0
-0,5
-1,6
-2,0";
+    use super::{Memory, Point, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let m = Memory::new(&INPUT, 6, 6);
-        assert_eq!(m.start, XY { x: 0, y: 0 });
-        assert_eq!(m.exit, XY { x: 6, y: 6 });
-        assert_eq!(m.bytes[4][5], 1);
+        let m = Memory::new(EXAMPLE, 6, 6).unwrap();
+        assert_eq!(m.start, Point::new(0, 0));
+        assert_eq!(m.exit, Point::new(6, 6));
+        assert_eq!(m.bytes[Point::new(5, 4)], 1);
     }
 
     #[test]
     fn test_part_1() {
-        let m = Memory::new(&INPUT, 6, 6);
+        let m = Memory::new(EXAMPLE, 6, 6).unwrap();
         assert_eq!(m.walk(12).unwrap(), 22);
     }
 
     #[test]
     fn test_part_2() {
-        let m = Memory::new(&INPUT, 6, 6);
-        assert_eq!(m.blocking_byte(0, m.max_byte as usize), XY { x: 6, y: 1 });
+        let m = Memory::new(EXAMPLE, 6, 6).unwrap();
+        assert_eq!(m.blocking_byte(0, m.max_byte as usize), Point::new(6, 1));
     }
 }
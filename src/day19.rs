@@ -1,6 +1,42 @@
+use crate::day::{self, Day, Example, ParseError};
 use std::collections::{HashMap};
 use crate::day19::Stripe::{BLACK, BLUE, GREEN, RED, WHITE};
 
+const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Puzzle;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("6"), Some("16")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+}
+
 pub fn part1(p: &Puzzle) -> u64 {
     p.designs.iter().filter(|&d| p.count_possible(d, false) > 0).count() as u64
 }
@@ -9,7 +45,7 @@ pub fn part2(p: &Puzzle) -> u64 {
     p.designs.iter().map(|d| p.count_possible(d, true)).sum()
 }
 
-pub fn generator(input: &str) -> Puzzle {
+pub fn generator(input: &str) -> Result<Puzzle, ParseError> {
     Puzzle::new(input)
 }
 
@@ -19,23 +55,23 @@ struct Tree {
 }
 
 impl Tree {
-    fn new(towels: &str) -> Self {
+    fn new(towels: &str) -> Result<Self, ParseError> {
         let mut tree: Tree = Tree::default();
         tree.nodes.push(Node::default());
         for towel in towels.split(", ") {
-            let stripes: Vec<char> = towel.chars().map(|c| c).collect();
+            let stripes = Stripe::parse(towel)?;
             tree.build_into_tree(0, &stripes);
         }
-        tree
+        Ok(tree)
     }
 
-    fn build_into_tree(&mut self, idx: usize, stripes: &[char]) {
+    fn build_into_tree(&mut self, idx: usize, stripes: &[Stripe]) {
         if stripes.is_empty() {
             self.nodes[idx].is_towel = true;
             return;
         }
 
-        let stripe = Stripe::new(&stripes[0]);
+        let stripe = stripes[0].clone();
         let idx = match self.nodes[idx].children.get(&stripe) {
             Some(idx) => *idx,
             None => {
@@ -102,18 +138,18 @@ pub struct Puzzle {
 }
 
 impl Puzzle {
-    pub fn new(input: &str) -> Puzzle {
-        let (towels, designs) = input.split_once("\n\n").unwrap();
-        let towels_as_tree = Tree::new(towels.trim());
+    pub fn new(input: &str) -> Result<Puzzle, ParseError> {
+        let (towels, designs) = day::split_once(input, "\n\n")?;
+        let towels_as_tree = Tree::new(towels.trim())?;
 
         let designs = designs.trim().lines()
-            .map(|d| d.chars().map(|c| Stripe::new(&c)).collect())
-            .collect();
+            .map(Stripe::parse)
+            .collect::<Result<_, _>>()?;
 
-        Puzzle {
+        Ok(Puzzle {
             towels_as_tree,
             designs,
-        }
+        })
     }
 
     fn count_possible(&self, design: &[Stripe], all: bool) -> u64 {
@@ -131,43 +167,38 @@ enum Stripe {
 }
 
 impl Stripe {
-    fn new(input: &char) -> Self {
+    fn new(input: &char) -> Option<Self> {
         match input {
-            'w' => WHITE,
-            'u' => BLUE,
-            'b' => BLACK,
-            'r' => RED,
-            'g' => GREEN,
-            _ => unreachable!("Found unknown stripe: {}", input),
+            'w' => Some(WHITE),
+            'u' => Some(BLUE),
+            'b' => Some(BLACK),
+            'r' => Some(RED),
+            'g' => Some(GREEN),
+            _ => None,
         }
     }
+
+    fn parse(input: &str) -> Result<Vec<Self>, ParseError> {
+        input.char_indices()
+            .map(|(i, c)| Stripe::new(&c).ok_or_else(|| ParseError::unexpected(&input[i..])))
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::day19::Stripe::{BLACK, BLUE, GREEN, RED, WHITE};
-    use super::{generator, part1, part2, Stripe};
-
-    const INPUT: &str = "r, wr, b, g, bwu, rb, gb, br
-
-brwrr
-bggr
-gbbr
-rrbgbr
-ubwu
-bwurrg
-brgr
-bbrgwb";
+    use super::{generator, part1, part2, Stripe, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let p = generator(&INPUT);
+        let p = generator(EXAMPLE).unwrap();
         assert_eq!(p.designs.len(), 8);
     }
 
     #[test]
     fn test_part_1() {
-        let p = generator(&INPUT);
+        let p = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&p), 6);
         assert_eq!(p.count_possible(&[RED], false), 1);
         assert_eq!(p.count_possible(&[RED, BLACK], false), 1);
@@ -181,14 +212,14 @@ bbrgwb";
         let p = generator("rrgbgg, gbgbgr, rrb, wrgggbb, rr, bgb, wbb, ruugr, gwugg, ruu, gubw, gru, bgg, uwu, bggwbrgw, wwugbr, ur, urb, bbb, rrbrbw, uww, wggwwu, wwrb, gbg, wuruu, wbgbwbr, ggu, grgwru, g, uugbuu, rwrrwb, uurwub, grgr, wubb, buwu, guu, bbw, rgwgbg, grr, uuuwr, ggubrrg, uuub, gugu, bur, bguub, rgr, bwu, bwbuw, wwwwbw, uurbbgb, rwb, uuw, ggurug, wrr, gww, rgbgu, wgr, rw, uguu, brb, ugrr, brwurgu, rugg, gbuwr, gb, uggbrbr, urwrbgb, rbgwbwb, gg, rur, uuu, wr, grur, uwugb, gbrugru, buug, bbrw, uurugru, u, bb, ubggb, brrb, rrg, bgur, gubub, gwwu, rugurug, ggbgbgbb, ggbwgr, gurw, gbgww, ugbg, gugb, grg, rggwg, buwb, wbu, guwu, rggbgugw, ggb, ruub, rugw, ugb, wrw, www, gwwbr, bru, bgurrbru, rrug, wubgug, brguw, wbugr, ggur, rgwuurrw, wurrg, rrgu, wwrwrb, brg, wwg, ugugu, ugr, rbgr, rg, uggg, rgrg, wwb, rrgrrwg, bgwb, brgbwgu, rurg, brw, brww, brggu, rgwwbbgg, uwgb, rwgggwwb, wrgrbw, bwrgw, wbbw, rbub, gbguuuub, bwrwg, uurruu, rugu, urrwguw, bwru, bu, wuub, ugggr, uuug, gu, uugggb, wbbb, rrr, b, rgwgr, gbr, gwrwwrw, ugu, ggw, wgub, wuw, uubr, ggrgbg, uwbbu, ubwurb, rbb, uur, uwrruug, wrggur, bwrr, wugb, wuwr, wwgb, wgubgg, wg, rgwbgwbw, rggugug, uub, uggug, wgu, brgrrub, rwwrgbr, gbgrgr, grgubbu, ugbbr, urw, ubuubb, burgw, ubu, gwgub, wwwubrgu, wwwww, bgr, bgu, gwwruuw, wgrgw, uwuu, wrb, ubw, rgrwu, brrrrr, wrgbb, gwuuwbg, wwbgu, uugbwrww, brwbg, ub, rbru, ruggwr, gwb, ggwrgg, ggurur, rgrrwrbw, wrgw, bugg, gbu, rgu, rwuw, gwg, uuwwu, uwbwww, wuwwbuu, wb, bbrr, rgrgwru, burr, ugurubu, gwu, buurb, wrgg, bugbrw, burbuw, bwr, urr, grgu, ruw, ugbwgw, rbrug, uuugr, buubwr, rurwg, ugrgw, grru, uwwwbur, uuwu, gwrwr, uruuu, urwgugbb, wrurwur, rwr, rgb, brbub, gwr, gwbb, brrbwb, bwwgw, bguw, ug, rru, wrg, uurrg, rww, bbu, wgbb, grw, ubg, uwg, ww, gbwr, rrwugr, wrrgwb, rrw, wbuuuub, wrurggr, bwrb, bbrbrgg, ggggbrw, ugug, wur, wwbr, gubbrwur, wgwgwb, wbbrr, wgw, rbwwbr, w, bbgw, urg, wbgbg, wbr, bbwbw, uu, wgwguw, wu, bgwgu, wub, buu, rb, bgbw, bgwgb, uwgrbu, guw, rbbgu, wru, rubgggwu, gur, rbrgw, gwug, wguwrr, gubbr, rub, rbwu, rgg, bggug, bwugb, rbbbgr, bbbwg, guwrrb, grrggg, bgwu, uuwbru, bbg, rug, uugu, gwugwu, ubb, gub, uubu, bwwrru, guub, wwr, urbgw, uru, wwu, wwwurwwu, bbwg, ubbbwg, gbug, bwbr, wgggwg, urgwub, bub, bg, uwrg, uwr, ubrrb, bug, rggru, rbw, ggrw, bbwwg, gr, gubu, uubbg, bwg, urggw, uuwrrgw, ugg, bbrg, gug, bwggww, grbb, bbbu, rbu, ru, ubuggr, ruuu, uug, uwb, wwwbug, uggwr, gbwuw, grwr, uwuwgbub, wwbw, bww, rbbwwr, bwww, ggggu, ugw, bwwwu, uuwug, wbbggrg, bwb, grbrgwu, bwwu, bw, ruurr, wbg, ubrg, uwuw, brrg, brwuu, gugw, urbggb, bruw, wbwuggw, ggwwr, rbwr, wbrgr, uugg, bbru, ugwuguwu, rwrg, bgwwwrw, uugwg, bwurrr, bwrwr, wwwrggu, bbr, rruwu, rgw, buw, urbrbg, wgg, gbgb, rwu, gubbu, gbgbgbrr, wbw, ubr, wgb, gw, buuwbr, rugrrur, gbuuwwr, rburubg, gbb, ugrgr, wug, ggggb, ggru, brwwg, bwwgrb, gurwubww, ggg, bwgb, bgugbw, rrrww, ggr, gbbb, bgw, bugu, wuu, grgur, rwg
 
 rugbgbwwbbgrwrbubgugrgbrrbgwrbbgbwurwgrbr
-uruuurbrwuwrrrwwurwbrwwguruwgrbgwbbwrugwwgrbr");
+uruuurbrwuwrrrwwurwbrwwguruwgrbgwbbwrugwwgrbr").unwrap();
         let input: [Stripe; 41] = [RED, BLUE, GREEN, BLACK, GREEN, BLACK, WHITE, WHITE, BLACK, BLACK, GREEN, RED, WHITE, RED, BLACK, BLUE, BLACK, GREEN, BLUE, GREEN, RED, GREEN, BLACK, RED, RED, BLACK, GREEN, WHITE, RED, BLACK, BLACK, GREEN, BLACK, WHITE, BLUE, RED, WHITE, GREEN, RED, BLACK, RED];
         assert_eq!(p.count_possible(&input, false), 0);
     }
 
     #[test]
     fn test_part_2() {
-        let p = generator(&INPUT);
+        let p = generator(EXAMPLE).unwrap();
         assert_eq!(16, part2(&p));
     }
 }
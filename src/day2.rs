@@ -1,8 +1,40 @@
+use crate::day::{self, Day, Example, ParseError};
 use std::iter::Iterator;
 
 const INCREASES: [i32; 3] = [1, 2, 3];
 const DECREASES: [i32; 3] = [-1, -2, -3];
 
+const EXAMPLE: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Vec<Vec<i32>>;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("2"), Some("4")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
+
 pub fn part1(reports: &Vec<Vec<i32>>) -> u32 {
     reports
         .iter()
@@ -55,12 +87,12 @@ pub fn part2(reports: &Vec<Vec<i32>>) -> u32 {
     reports.iter().map(|r| is_safe(r, true)).sum()
 }
 
-pub fn generator(input: &str) -> Vec<Vec<i32>> {
+pub fn generator(input: &str) -> Result<Vec<Vec<i32>>, ParseError> {
     input
         .lines()
         .map(|line| {
             line.split_whitespace()
-                .map(|num| num.parse::<i32>().unwrap())
+                .map(day::parse::<i32>)
                 .collect()
         })
         .collect()
@@ -68,18 +100,11 @@ pub fn generator(input: &str) -> Vec<Vec<i32>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, is_safe, part1, part2};
-
-    const INPUT: &str = "7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9";
+    use super::{generator, is_safe, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let reports = generator(&INPUT);
+        let reports = generator(EXAMPLE).unwrap();
         assert_eq!(reports.len(), 6);
         assert_eq!(reports[0], vec![7, 6, 4, 2, 1]);
         assert_eq!(reports[1], vec![1, 2, 7, 8, 9]);
@@ -87,13 +112,13 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let reports = generator(&INPUT);
+        let reports = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&reports), 2);
     }
 
     #[test]
     fn test_part_2() {
-        let reports = generator(&INPUT);
+        let reports = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&reports), 4);
     }
 
@@ -1,16 +1,76 @@
+use crate::day::{Day, Example, ParseError};
+use crate::params::{Param, Params};
 use crate::day20::Space::{TRACK, WALL};
+use crate::utils::grid::{Grid, Point};
+use crate::utils::search::{self, Graph};
 
-const NEIGHBORS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const SHORT_CHEAT: u32 = 2;
+const LONG_CHEAT: u32 = 20;
+const SAVING: u32 = 100;
+
+const EXAMPLE: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = RaceTrack;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("1"), Some("285")], params: &[("saving", 50)] },
+    ];
+
+    const PARAMS: &'static [Param] = &[
+        ("cheat1", SHORT_CHEAT as i64, 0..=u32::MAX as i64),
+        ("cheat2", LONG_CHEAT as i64, 0..=u32::MAX as i64),
+        ("saving", SAVING as i64, 0..=u32::MAX as i64),
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part1_with(input: &Self::Input, params: &Params) -> u32 {
+        input.compute_savings(params.get("cheat1") as u32, params.get("saving") as u32, true)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+
+    fn part2_with(input: &Self::Input, params: &Params) -> u32 {
+        input.compute_savings(params.get("cheat2") as u32, params.get("saving") as u32, true)
+    }
+}
 
 pub fn part1(race_track: &RaceTrack) -> u32 {
-    race_track.compute_savings(2, 100, true)
+    race_track.compute_savings(SHORT_CHEAT, SAVING, true)
 }
 
 pub fn part2(race_track: &RaceTrack) -> u32 {
-    race_track.compute_savings(20, 100, true)
+    race_track.compute_savings(LONG_CHEAT, SAVING, true)
 }
 
-pub fn generator(input: &str) -> RaceTrack {
+pub fn generator(input: &str) -> Result<RaceTrack, ParseError> {
     RaceTrack::new(input)
 }
 
@@ -21,81 +81,55 @@ enum Space {
 }
 
 impl Space {
-    fn new(space: &char) -> Space {
+    fn new(space: &char) -> Option<Space> {
         match space {
-            '.' | 'S' | 'E' => TRACK,
-            '#' => WALL,
-            _ => unreachable!("Invalid space: {}", space),
+            '.' | 'S' | 'E' => Some(TRACK),
+            '#' => Some(WALL),
+            _ => None,
         }
     }
 }
 
-#[derive(Debug, Eq, PartialEq, Clone, Ord, PartialOrd, Hash)]
-struct XY {
-    x: i32,
-    y: i32,
-}
+/// The race track as a graph of the cells that can be driven through.
+struct Track(Grid<Space>);
+
+impl Graph for Track {
+    type Node = Point;
+    type Map<V: Clone> = Grid<Option<V>>;
 
-impl XY {
-    fn is_same(&self, x: i32, y: i32) -> bool {
-        self.x == x && self.y == y
+    fn map<V: Clone>(&self) -> Grid<Option<V>> {
+        Grid::new(self.0.width(), self.0.height(), None)
     }
 
-    fn manhattan_distance(&self, other: &XY) -> u32 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    fn neighbors(&self, p: &Point) -> impl Iterator<Item = (Point, u32)> {
+        self.0.neighbors(*p).filter(|n| self.0[*n] == TRACK).map(|n| (n, 1))
     }
 }
 
 pub struct RaceTrack {
-    path: Vec<XY>,
+    path: Vec<Point>,
 }
 
 impl RaceTrack {
-    fn new(input: &str) -> Self {
-        let mut start = None;
-        let mut end = None;
-        let map = input.lines().enumerate().map(|(y, line)| {
-            line.chars().enumerate().map(|(x, c)| {
-                if c == 'S' {
-                    start = Some(XY { x: x as i32, y: y as i32 });
-                } else if c == 'E' {
-                    end = Some(XY { x: x as i32, y: y as i32 });
-                }
-                Space::new(&c)
-            }).collect()
-        }).collect();
-        let path = Self::compute_path(map, start.unwrap(), end.unwrap());
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (map, [start, end]) = Grid::parse_with_markers(input, ['S', 'E'], |c| Space::new(&c))?;
+        let path = Self::compute_path(Track(map), start, end)
+            .ok_or_else(|| ParseError::invalid(input, "no track from the start to the end"))?;
 
-        Self {
+        Ok(Self {
             path
-        }
+        })
     }
 
-    fn compute_path(map: Vec<Vec<Space>>, start: XY, end: XY) -> Vec<XY> {
-        let mut path = Vec::new();
-        let mut curr = start.clone();
-        path.push(start);
-        while curr != end {
-            for (dx, dy) in NEIGHBORS.iter() {
-                if curr.y + dy > -1 && curr.y + dy < map.len() as i32
-                    && curr.x + dx > -1 && curr.x + dx < map[0].len() as i32
-                    && (path.len() == 1 || !path[path.len() - 2].is_same(curr.x + dx, curr.y + dy))
-                    && map[(curr.y + dy) as usize][(curr.x + dx) as usize] == TRACK {
-                    curr.x += dx;
-                    curr.y += dy;
-                    path.push(curr.clone());
-                    break;
-                }
-            }
-        }
-        path
+    fn compute_path(track: Track, start: Point, end: Point) -> Option<Vec<Point>> {
+        search::bfs(&track, start, |p| *p == end).path(&end)
     }
 
     fn compute_savings(&self, cheat_time: u32, saving: u32, minimum: bool) -> u32 {
         let mut cheats = 0;
-        for i in 0..self.path.len() - saving as usize {
+        for i in 0..self.path.len().saturating_sub(saving as usize) {
             for j in i + saving as usize..self.path.iter().len() {
-                match self.path[i].manhattan_distance(&self.path[j]) {
+                match self.path[i].manhattan(self.path[j]) {
                     d if d <= cheat_time && j as u32 - i as u32 - d == saving => {
                         cheats += 1;
                     }
@@ -112,35 +146,19 @@ impl RaceTrack {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, XY};
-
-    const INPUT: &str = "###############
-#...#...#.....#
-#.#.#.#.#.###.#
-#S#...#.#.#...#
-#######.#.#.###
-#######.#.#...#
-#######.#.###.#
-###..E#...#...#
-###.#######.###
-#...###...#...#
-#.#####.#.###.#
-#.#...#.#.#...#
-#.#.#.#.#.#.###
-#...#...#...###
-###############";
+    use super::{generator, Point, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let rt = generator(&INPUT);
-        assert_eq!(rt.path[0], XY { x: 1, y: 3 });
-        assert_eq!(rt.path.last().unwrap(), &XY { x: 5, y: 7 });
+        let rt = generator(EXAMPLE).unwrap();
+        assert_eq!(rt.path[0], Point::new(1, 3));
+        assert_eq!(rt.path.last().unwrap(), &Point::new(5, 7));
         assert_eq!(rt.path.len(), 85);
     }
 
     #[test]
     fn test_part_1() {
-        let rt = generator(&INPUT);
+        let rt = generator(EXAMPLE).unwrap();
 
         assert_eq!(14, rt.compute_savings(2, 4, false));
         assert_eq!(2, rt.compute_savings(2, 6, false));
@@ -156,7 +174,7 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        let rt = generator(&INPUT);
+        let rt = generator(EXAMPLE).unwrap();
         assert_eq!(1, rt.compute_savings(6, 76, false));
 
         assert_eq!(32, rt.compute_savings(20, 50, false));
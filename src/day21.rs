@@ -1,3 +1,5 @@
+use crate::day::{Day, Example, ParseError};
+use crate::params::{Param, Params};
 use std::collections::HashMap;
 use std::fmt::Display;
 use crate::day21::DKey::{ADir, Down, EDir, Left, Right, Up};
@@ -15,18 +17,64 @@ const NUMERIC_PAD: [[NKey; 3]; 4] = [
     [NKey::ENum, Zero, ANum]
 ];
 
+const ROBOTS_1: usize = 2;
+const ROBOTS_2: usize = 25;
+// More robots than this and the number of presses doesn't fit in a u64
+const MAX_ROBOTS: i64 = 35;
+
+const EXAMPLE: &str = "029A
+980A
+179A
+456A
+379A";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Vec<Vec<NKey>>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("126384"), Some("154115708116294")], params: &[] },
+    ];
+
+    const PARAMS: &'static [Param] = &[
+        ("robots1", ROBOTS_1 as i64, 1..=MAX_ROBOTS),
+        ("robots2", ROBOTS_2 as i64, 1..=MAX_ROBOTS),
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part1_with(input: &Self::Input, params: &Params) -> u64 {
+        complexity(input, params.get("robots1") as usize)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+
+    fn part2_with(input: &Self::Input, params: &Params) -> u64 {
+        complexity(input, params.get("robots2") as usize)
+    }
+}
+
 pub fn part1(codes: &Vec<Vec<NKey>>) -> u64 {
-    let mut p = Puzzle::new(2);
-    codes.iter()
-        .map(|code| {
-            let l = p.press_keys(code);
-            let v = NKey::value(code) as u64;
-            l * v
-        }).sum()
+    complexity(codes, ROBOTS_1)
 }
 
 pub fn part2(codes: &Vec<Vec<NKey>>) -> u64 {
-    let mut p = Puzzle::new(25);
+    complexity(codes, ROBOTS_2)
+}
+
+fn complexity(codes: &[Vec<NKey>], robots: usize) -> u64 {
+    let mut p = Puzzle::new(robots);
     codes.iter()
         .map(|code| {
             let l = p.press_keys(code);
@@ -35,11 +83,11 @@ pub fn part2(codes: &Vec<Vec<NKey>>) -> u64 {
         }).sum()
 }
 
-pub fn generator(input: &str) -> Vec<Vec<NKey>> {
+pub fn generator(input: &str) -> Result<Vec<Vec<NKey>>, ParseError> {
     input.lines()
         .map(|line| {
-            line.chars()
-                .map(NKey::new)
+            line.char_indices()
+                .map(|(i, c)| NKey::new(c).ok_or_else(|| ParseError::unexpected(&line[i..])))
                 .collect()
         }).collect()
 }
@@ -177,20 +225,20 @@ pub enum NKey {
 }
 
 impl NKey {
-    fn new(c: char) -> Self {
+    fn new(c: char) -> Option<Self> {
         match c {
-            'A' => ANum,
-            '0' => Zero,
-            '1' => One,
-            '2' => Two,
-            '3' => Three,
-            '4' => Four,
-            '5' => Five,
-            '6' => Six,
-            '7' => Seven,
-            '8' => Eight,
-            '9' => Nine,
-            _ => unreachable!()
+            'A' => Some(ANum),
+            '0' => Some(Zero),
+            '1' => Some(One),
+            '2' => Some(Two),
+            '3' => Some(Three),
+            '4' => Some(Four),
+            '5' => Some(Five),
+            '6' => Some(Six),
+            '7' => Some(Seven),
+            '8' => Some(Eight),
+            '9' => Some(Nine),
+            _ => None,
         }
     }
 
@@ -303,17 +351,11 @@ impl NPad {
 mod tests {
     use crate::day21::DKey::{ADir, Down, Left, Right, Up};
     use crate::day21::NKey::{ANum, Eight, Nine, One, Two, Zero, Seven};
-    use super::{generator, part1, part2, DPad, NPad, Puzzle};
-
-    const INPUT: &str = "029A
-980A
-179A
-456A
-379A";
+    use super::{generator, part1, part2, DPad, NPad, Puzzle, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let codes = generator(&INPUT);
+        let codes = generator(EXAMPLE).unwrap();
         assert_eq!(codes[0], [Zero, Two, Nine, ANum]);
         assert_eq!(codes[1], [Nine, Eight, Zero, ANum]);
     }
@@ -336,13 +378,13 @@ mod tests {
         assert_eq!(result,
                    "<vA<AA>>^AvAA<^A>A<v<A>>^AvA^A<vA>^A<v<A>^A>AAvA^A<v<A>A>^AAAvA<^A>A".len() as u64);
 
-        let codes = generator(&INPUT);
+        let codes = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&codes), 126384);
     }
 
     #[test]
     fn test_part_2() {
-        let p = generator(&INPUT);
+        let p = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&p), 154115708116294);
     }
 }
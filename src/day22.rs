@@ -1,19 +1,77 @@
+use crate::day::{self, Day, Example, ParseError};
+use crate::params::{Param, Params};
 use std::collections::{HashMap, VecDeque};
 use itertools::Itertools;
 
 const PRUNE_VALUE: u32 = 16777216 - 1;
 const PRICE_CHANGES: usize = 4;
+const SECRETS: usize = 2000;
+const MAX_SECRETS: i64 = 100000;
+
+const EXAMPLE: &str = "1
+10
+100
+2024";
+const EXAMPLE_2: &str = "1
+2
+3
+2024";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Secrets;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("37327623"), None], params: &[] },
+        Example { input: EXAMPLE_2, answers: [None, Some("23")], params: &[] },
+    ];
+
+    const PARAMS: &'static [Param] = &[
+        ("secrets", SECRETS as i64, PRICE_CHANGES as i64 + 1..=MAX_SECRETS),
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part1_with(input: &Self::Input, params: &Params) -> u64 {
+        final_secrets(input, params.get("secrets") as usize)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+
+    fn part2_with(input: &Self::Input, params: &Params) -> u64 {
+        most_bananas(input, params.get("secrets") as usize)
+    }
+}
 
 pub fn part1(secrets: &Secrets) -> u64 {
-    secrets.numbers.iter().map(|n| *secrets.compute_n_secrets(*n, 2000).last().unwrap() as u64)
-        .sum()
+    final_secrets(secrets, SECRETS)
 }
 
 pub fn part2(secrets: &Secrets) -> u64 {
+    most_bananas(secrets, SECRETS)
+}
+
+fn final_secrets(secrets: &Secrets, n: usize) -> u64 {
+    secrets.numbers.iter().map(|s| *secrets.compute_n_secrets(*s, n).last().unwrap() as u64)
+        .sum()
+}
+
+fn most_bananas(secrets: &Secrets, n: usize) -> u64 {
     let mut total_sequences = HashMap::new();
 
     for secret in secrets.numbers.iter() {
-        let mut sequences = secrets.get_sequences(*secret, 2000);
+        let mut sequences = secrets.get_sequences(*secret, n);
         for (s, v) in sequences.drain() {
             total_sequences.entry(s)
                 .and_modify(|total| *total += v as u64)
@@ -24,7 +82,7 @@ pub fn part2(secrets: &Secrets) -> u64 {
     *total_sequences.values().max().unwrap()
 }
 
-pub fn generator(input: &str) -> Secrets {
+pub fn generator(input: &str) -> Result<Secrets, ParseError> {
     Secrets::new(input)
 }
 
@@ -33,11 +91,11 @@ pub struct Secrets {
 }
 
 impl Secrets {
-    fn new(input: &str) -> Self {
-        let numbers = input.lines().map(|l| l.parse().unwrap()).collect();
-        Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let numbers = input.lines().map(day::parse).collect::<Result<_, _>>()?;
+        Ok(Self {
             numbers,
-        }
+        })
     }
 
     fn compute_n_secrets(&self, start: u32, n: usize) -> Vec<u32> {
@@ -86,16 +144,11 @@ impl Secrets {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "1
-10
-100
-2024";
+    use super::{generator, part1, part2, EXAMPLE, EXAMPLE_2};
 
     #[test]
     fn test_generator() {
-        let s = generator(&INPUT);
+        let s = generator(EXAMPLE).unwrap();
         assert_eq!(s.numbers[0], 1);
         assert_eq!(s.numbers[1], 10);
         assert_eq!(s.numbers[2], 100);
@@ -105,7 +158,7 @@ mod tests {
 
     #[test]
     fn test_next_secret() {
-        let s = generator(&INPUT);
+        let s = generator(EXAMPLE).unwrap();
         assert_eq!(s.next_secret(123), 15887950);
         assert_eq!(s.next_secret(15887950), 16495136);
         assert_eq!(s.next_secret(16495136), 527345);
@@ -116,7 +169,7 @@ mod tests {
 
     #[test]
     fn test_part_1() {
-        let s = generator(&INPUT);
+        let s = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&s), 37327623);
     }
 
@@ -125,10 +178,7 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        let s = generator("1
-2
-3
-2024");
+        let s = generator(EXAMPLE_2).unwrap();
         assert_eq!(part2(&s), 23);
     }
 }
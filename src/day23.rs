@@ -1,6 +1,64 @@
+use crate::day::{self, Day, Example, ParseError};
 use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
 
+const EXAMPLE: &str = "kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Network;
+    type Output1 = u32;
+    type Output2 = String;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("7"), Some("co,de,ka,ta")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> String {
+        part2(input)
+    }
+}
+
 pub fn part1(n: &Network) -> u32 {
     let sets = n.all_inter_connected(3);
     sets.iter()
@@ -16,7 +74,7 @@ pub fn part2(n: &Network) -> String {
     r.join(",")
 }
 
-pub fn generator(input: &str) -> Network {
+pub fn generator(input: &str) -> Result<Network, ParseError> {
     Network::new(input)
 }
 
@@ -27,13 +85,13 @@ pub struct Network {
 }
 
 impl Network {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut host_id_map: HashMap<String, u32> = HashMap::new();
         let mut id_host_map: Vec<String> = Vec::new();
         let mut connections: HashMap<u32, Vec<u32>> = HashMap::new();
 
-        input.lines().for_each(|l| {
-            let (c1, c2) = l.split_once('-').unwrap();
+        for l in input.lines() {
+            let (c1, c2) = day::split_once(l, "-")?;
             let id1 = *host_id_map.entry(c1.to_string()).or_insert(id_host_map.len() as u32);
             if id_host_map.len() < host_id_map.len() { id_host_map.push(c1.to_owned()); }
             let id2 = *host_id_map.entry(c2.to_string()).or_insert(id_host_map.len() as u32);
@@ -45,9 +103,9 @@ impl Network {
             connections.entry(id2)
                 .and_modify(|v| v.push(id1))
                 .or_insert(vec![id1]);
-        });
+        }
 
-        Network { id_host_map, _host_id_map: host_id_map, connections }
+        Ok(Network { id_host_map, _host_id_map: host_id_map, connections })
     }
 
     fn _get_id(&self, host: &str) -> &u32 {
@@ -97,44 +155,11 @@ impl Network {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "kh-tc
-qp-kh
-de-cg
-ka-co
-yn-aq
-qp-ub
-cg-tb
-vc-aq
-tb-ka
-wh-tc
-yn-cg
-kh-ub
-ta-co
-de-co
-tc-td
-tb-wq
-wh-td
-ta-ka
-td-qp
-aq-cg
-wq-ub
-ub-vc
-de-ta
-wq-aq
-wq-vc
-wh-yn
-ka-de
-kh-ta
-co-tc
-wh-qp
-tb-vc
-td-yn";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let n = generator(&INPUT);
+        let n = generator(EXAMPLE).unwrap();
 
         assert_eq!(n.connections.get(n._get_id("kh")).unwrap().len(), 4);
         assert_eq!(n.connections.get(n._get_id("qp")).unwrap().len(), 4);
@@ -142,13 +167,13 @@ td-yn";
 
     #[test]
     fn test_part_1() {
-        let n = generator(&INPUT);
+        let n = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&n), 7);
     }
 
     #[test]
     fn test_part_2() {
-        let n = generator(&INPUT);
+        let n = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&n), "co,de,ka,ta");
     }
 }
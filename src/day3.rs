@@ -1,5 +1,34 @@
+use crate::day::{Day, Example, ParseError};
 use std::iter::Iterator;
 
+const EXAMPLE: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+const EXAMPLE_2: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = String;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("161"), None], params: &[] },
+        Example { input: EXAMPLE_2, answers: [None, Some("48")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
+
 pub fn part1(instructions: &str) -> u32 {
     instructions.split("mul(")
         .filter_map(|x| {
@@ -28,30 +57,28 @@ pub fn part2(instructions: &str) -> u32 {
         .sum()
 }
 
-pub fn generator(input: &str) -> String {
-    input.to_string()
+pub fn generator(input: &str) -> Result<String, ParseError> {
+    Ok(input.to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+    use super::{generator, part1, part2, EXAMPLE, EXAMPLE_2};
 
     #[test]
     fn test_generator() {
-        let s = generator(&INPUT);
-        assert_eq!(&s, INPUT);
+        let s = generator(EXAMPLE).unwrap();
+        assert_eq!(&s, EXAMPLE);
     }
 
     #[test]
     fn test_part_1() {
-        let instructions = generator(&INPUT);
+        let instructions = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&instructions), 161);
     }
 
     #[test]
     fn test_part_2() {
-        assert_eq!(part2("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"), 48);
+        assert_eq!(part2(EXAMPLE_2), 48);
     }
 }
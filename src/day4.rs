@@ -1,36 +1,61 @@
-use std::iter::Iterator;
+use crate::day::{Day, Example, ParseError};
+use crate::utils::grid::{Grid, Point};
 
-pub fn part1(word_search: &Vec<Vec<char>>) -> usize {
+const EXAMPLE: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Grid<char>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("18"), Some("9")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> usize {
+        part2(input)
+    }
+}
+
+pub fn part1(word_search: &Grid<char>) -> usize {
     let directions = vec![
-        vec![(1, 0), (2, 0), (3, 0)],         // horizontal
-        vec![(0, -1), (0, -2), (0, -3)],      // vertical
-        vec![(-1, -1), (-2, -2), (-3, -3)],   // backslash
-        vec![(-1, 1), (-2, 2), (-3, 3)]       // forward slash
+        vec![Point::new(1, 0), Point::new(2, 0), Point::new(3, 0)],         // horizontal
+        vec![Point::new(0, -1), Point::new(0, -2), Point::new(0, -3)],      // vertical
+        vec![Point::new(-1, -1), Point::new(-2, -2), Point::new(-3, -3)],   // backslash
+        vec![Point::new(-1, 1), Point::new(-2, 2), Point::new(-3, 3)]       // forward slash
     ];
     let expected = ['M', 'A', 'S'];
-    let mut count = 0;
-    for y in 0..word_search.len() {
-        for x in 0..word_search[0].len() {
-            if word_search[y][x] == 'X' {
-                count += count_occurrence(word_search, x as i32, y as i32, &directions, &expected)
-            }
-        }
-    }
-    count
+    word_search.iter()
+        .filter(|(_, c)| **c == 'X')
+        .map(|(p, _)| count_occurrence(word_search, p, &directions, &expected))
+        .sum()
 }
 
-fn count_occurrence(word_search: &Vec<Vec<char>>, x: i32, y: i32,
-                    directions: &[Vec<(i32, i32)>], expected: &[char]) -> usize {
+fn count_occurrence(word_search: &Grid<char>, p: Point,
+                    directions: &[Vec<Point>], expected: &[char]) -> usize {
     let mut count = 0;
     for dir in directions {
         for direction in [-1, 1] {
-            if dir.iter().zip(expected).all(|((dx, dy), c)| {
-                let dx = dx * direction;
-                let dy = dy * direction;
-                x + dx > -1 && x + dx < word_search[0].len() as i32
-                    && y + dy > -1 && y + dy < word_search.len() as i32
-                    && word_search[(y + dy) as usize][(x + dx) as usize] == *c
-            }) {
+            if dir.iter().zip(expected).all(|(d, c)| word_search.get(p + *d * direction) == Some(c)) {
                 count += 1;
             }
         }
@@ -38,62 +63,41 @@ fn count_occurrence(word_search: &Vec<Vec<char>>, x: i32, y: i32,
     count
 }
 
-pub fn part2(word_search: &Vec<Vec<char>>) -> usize {
-    let mut count = 0;
+pub fn part2(word_search: &Grid<char>) -> usize {
     let directions = vec![
-        vec![(-1, -1), (1, 1)],  // backslash
-        vec![(-1, 1), (1, -1)]   // forward slash
+        vec![Point::new(-1, -1), Point::new(1, 1)],  // backslash
+        vec![Point::new(-1, 1), Point::new(1, -1)]   // forward slash
     ];
     let expected = ['M', 'S'];
-    for y in 0..word_search.len() {
-        for x in 0..word_search[0].len() {
-            if word_search[y][x] == 'A' {
-                if count_occurrence(word_search, x as i32, y as i32, &directions, &expected) == 2 {
-                    count += 1
-                }
-            }
-        }
-    }
-    count
+    word_search.iter()
+        .filter(|(p, c)| **c == 'A' && count_occurrence(word_search, *p, &directions, &expected) == 2)
+        .count()
 }
 
-pub fn generator(input: &str) -> Vec<Vec<char>> {
-    input.lines()
-         .map(|line| line.chars().collect())
-         .collect()
+pub fn generator(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse(input, Some)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let word_search = generator(&INPUT);
-        assert_eq!(word_search.len(), 10);
-        assert_eq!(word_search[0].len(), 10);
+        let word_search = generator(EXAMPLE).unwrap();
+        assert_eq!(word_search.height(), 10);
+        assert_eq!(word_search.width(), 10);
     }
 
     #[test]
     fn test_part_1() {
-        let word_search = generator(&INPUT);
+        let word_search = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&word_search), 18);
     }
 
     #[test]
     fn test_part_2() {
-        let word_search = generator(&INPUT);
+        let word_search = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&word_search), 9);
     }
 }
@@ -1,6 +1,60 @@
+use crate::day::{self, Day, Example, ParseError};
 use std::collections::HashSet;
 use std::iter::Iterator;
 
+const EXAMPLE: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Puzzle;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("143"), Some("123")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
+
 pub fn part1(puzzle: &Puzzle) -> u32 {
     puzzle.score_valid_updates()
 }
@@ -9,7 +63,7 @@ pub fn part2(puzzle: &Puzzle) -> u32 {
     puzzle.score_invalid_updates()
 }
 
-pub fn generator(input: &str) -> Puzzle {
+pub fn generator(input: &str) -> Result<Puzzle, ParseError> {
     Puzzle::new(input)
 }
 
@@ -19,19 +73,19 @@ pub struct Puzzle {
 }
 
 impl Puzzle {
-    fn new(input: &str) -> Self {
-        let (rules, updates) = input.split_once("\n\n").unwrap();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (rules, updates) = day::split_once(input, "\n\n")?;
         let rules: HashSet<(u32, u32)> = rules.lines()
             .map(|l| {
-                let (b, a) = l.split_once("|").unwrap();
-                (b.parse().unwrap(), a.parse().unwrap())
-            }).collect();
+                let (b, a) = day::split_once(l, "|")?;
+                Ok((day::parse(b)?, day::parse(a)?))
+            }).collect::<Result<_, ParseError>>()?;
 
         let mut valid = Vec::new();
         let mut invalid: Vec<Vec<(u32, i32)>> = Vec::new();
 
-        updates.lines().for_each(|line| {
-            let u: Vec<u32> = line.split(',').map(|s| s.parse().unwrap()).collect();
+        for line in updates.lines() {
+            let u: Vec<u32> = line.split(',').map(day::parse).collect::<Result<_, _>>()?;
             let mut pass = true;
             let mut ordering = vec![0i32; u.len()];
             for i in 0..u.len() - 1 {
@@ -51,12 +105,12 @@ impl Puzzle {
             } else {
                 invalid.push(u.iter().zip(ordering).map(|(&p, o)| (p, o)).collect());
             }
-        });
+        }
 
-        Puzzle {
+        Ok(Puzzle {
             valid,
             invalid,
-        }
+        })
     }
 
     fn score_valid_updates(&self) -> u32 {
@@ -80,52 +134,23 @@ impl Puzzle {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let puzzle = generator(&INPUT);
+        let puzzle = generator(EXAMPLE).unwrap();
         assert_eq!(puzzle.valid.len() + puzzle.invalid.len(), 6);
     }
 
     #[test]
     fn test_part_1() {
-        let puzzle = generator(&INPUT);
+        let puzzle = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&puzzle), 143);
     }
 
     #[test]
     fn test_part_2() {
-        let puzzle = generator(&INPUT);
+        let puzzle = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&puzzle), 123);
     }
 }
@@ -1,5 +1,40 @@
-use std::iter::Iterator;
-use std::mem::swap;
+use crate::day::{Day, Example, ParseError};
+use crate::utils::grid::{Grid, Point};
+
+const EXAMPLE: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = PatrolMap;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("41"), Some("6")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
+    }
+}
 
 pub fn part1(pm: &PatrolMap) -> u32 {
     pm.patrol().len() as u32
@@ -14,149 +49,81 @@ pub fn part2(pm: &PatrolMap) -> u32 {
         .count() as u32
 }
 
-pub fn generator(input: &str) -> PatrolMap {
+pub fn generator(input: &str) -> Result<PatrolMap, ParseError> {
     PatrolMap::new(input)
 }
 
-fn turn_right(dir: &mut (i32, i32)) {
-    swap(&mut dir.0, &mut dir.1);
-    if dir.1 == 0 {
-        dir.0 = -dir.0;
-    }
-}
-
-fn dir_to_idx(dir: &(i32, i32)) -> usize {
+fn dir_to_idx(dir: Point) -> usize {
     match dir {
-        (-1, 0) => 0,
-        (0, -1) => 1,
-        (1, 0) => 2,
-        (0, 1) => 3,
+        Point::LEFT => 0,
+        Point::UP => 1,
+        Point::RIGHT => 2,
+        Point::DOWN => 3,
         _ => unreachable!(),
     }
 }
 
 pub struct PatrolMap {
-    obstacles: Vec<Vec<bool>>,
-    guard_start: (i32, i32),
-    guard_direction: (i32, i32),
-    width: i32,
-    height: i32,
+    obstacles: Grid<bool>,
+    guard_start: Point,
+    guard_direction: Point,
 }
 
 impl PatrolMap {
-    fn new(input: &str) -> Self {
-        let mut y = -1;
-        let mut obstacles = Vec::new();
-        let mut guard_start = (0, 0);
-        let mut guard_direction = (0, 0);
-        let width = input.lines().next().unwrap().chars().count() as i32;
-
-        for line in input.lines() {
-            y += 1;
-            let mut row = vec![false; width as usize];
-            for (x, c) in line.chars().enumerate() {
-                match c {
-                    '#' => {
-                        row[x] = true;
-                    }
-                    '^' => {
-                        guard_start = (x as i32, y);
-                        guard_direction = (0, -1);
-                    }
-                    '>' => {
-                        guard_start = (x as i32, y);
-                        guard_direction = (1, 0);
-                    }
-                    'v' => {
-                        guard_start = (x as i32, y);
-                        guard_direction = (0, -1);
-                    }
-                    '<' => {
-                        guard_start = (x as i32, y);
-                        guard_direction = (-1, 0);
-                    }
-                    _ => {
-                        // Ignore paths
-                    }
-                }
-            }
-            obstacles.push(row);
-        }
-
-        let height = obstacles.len() as i32;
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::parse(input, |c| "#.^>v<".contains(c).then_some(c))?;
+        let guard_start = map.position(|c| Point::from_arrow(*c).is_some())
+            .ok_or_else(|| ParseError::missing(input, "guard"))?;
 
-        PatrolMap {
-            obstacles,
+        Ok(PatrolMap {
+            obstacles: map.map(|c| *c == '#'),
             guard_start,
-            guard_direction,
-            height,
-            width,
-        }
+            guard_direction: Point::from_arrow(map[guard_start]).unwrap(),
+        })
     }
 
-    fn is_valid_pos(&self, curr: &(i32, i32)) -> bool {
-        curr.0 > -1 && curr.0 < self.width
-            && curr.1 > -1 && curr.1 < self.height
-    }
-
-    fn patrol(&self) -> Vec<(i32, i32)> {
-        let mut curr = self.guard_start.clone();
-        let mut dir = self.guard_direction.clone();
-        let mut visited = vec![vec![false; self.width as usize]; self.height as usize];
-        while self.is_valid_pos(&curr) {
-            visited[curr.1 as usize][curr.0 as usize] = true;
+    fn patrol(&self) -> Vec<Point> {
+        let mut curr = self.guard_start;
+        let mut dir = self.guard_direction;
+        let mut visited = self.obstacles.map(|_| false);
+        while self.obstacles.contains(curr) {
+            visited[curr] = true;
 
-            curr.0 += dir.0;
-            curr.1 += dir.1;
+            curr += dir;
             self.point_away_from_obstacle(&mut curr, &mut dir);
         }
 
-        visited.iter().enumerate()
-            .flat_map(|(y, r)| {
-                r.iter().enumerate().filter_map(move |(x, v)| {
-                    if *v {
-                        Some((x as i32, y as i32))
-                    } else {
-                        None
-                    }
-                })
-            }).collect()
+        visited.iter().filter(|(_, v)| **v).map(|(p, _)| p).collect()
     }
 
-    fn point_away_from_obstacle(&self, curr: &mut (i32, i32), dir: &mut (i32, i32)) {
-        while self.is_valid_pos(curr) && self.obstacles[curr.1 as usize][curr.0 as usize] {
-            curr.0 -= dir.0;
-            curr.1 -= dir.1;
-            turn_right(dir);
-            curr.0 += dir.0;
-            curr.1 += dir.1;
+    fn point_away_from_obstacle(&self, curr: &mut Point, dir: &mut Point) {
+        while self.obstacles.get(*curr) == Some(&true) {
+            *curr -= *dir;
+            *dir = dir.turn_right();
+            *curr += *dir;
         }
     }
 
-    fn point_away_from_obstacle_w_new(&self, curr: &mut (i32, i32), dir: &mut (i32, i32), new_obstacle: &(i32, i32)) {
-        while self.is_valid_pos(curr)
-            && (self.obstacles[curr.1 as usize][curr.0 as usize] || new_obstacle == curr) {
-            curr.0 -= dir.0;
-            curr.1 -= dir.1;
-            turn_right(dir);
-            curr.0 += dir.0;
-            curr.1 += dir.1;
+    fn point_away_from_obstacle_w_new(&self, curr: &mut Point, dir: &mut Point, new_obstacle: &Point) {
+        while self.obstacles.get(*curr).is_some_and(|o| *o || new_obstacle == curr) {
+            *curr -= *dir;
+            *dir = dir.turn_right();
+            *curr += *dir;
         }
     }
 
-    fn is_stuck_in_loop(&self, new_obstacle: &(i32, i32)) -> bool {
-        let mut curr = self.guard_start.clone();
-        let mut dir = self.guard_direction.clone();
-        let mut visited = vec![vec![[false; 4]; self.width as usize]; self.height as usize];
-        while self.is_valid_pos(&curr) {
-            if visited[curr.1 as usize][curr.0 as usize][dir_to_idx(&dir)] {
+    fn is_stuck_in_loop(&self, new_obstacle: &Point) -> bool {
+        let mut curr = self.guard_start;
+        let mut dir = self.guard_direction;
+        let mut visited = self.obstacles.map(|_| [false; 4]);
+        while self.obstacles.contains(curr) {
+            if visited[curr][dir_to_idx(dir)] {
                 return true;
             }
-            visited[curr.1 as usize][curr.0 as usize][dir_to_idx(&dir)] = true;
+            visited[curr][dir_to_idx(dir)] = true;
 
-            curr.0 += dir.0;
-            curr.1 += dir.1;
-            self.point_away_from_obstacle_w_new(&mut curr, &mut dir, &new_obstacle);
+            curr += dir;
+            self.point_away_from_obstacle_w_new(&mut curr, &mut dir, new_obstacle);
         }
         false
     }
@@ -164,53 +131,28 @@ impl PatrolMap {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2, turn_right};
-
-    const INPUT: &str = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...";
+    use super::{generator, part1, part2, Point, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let pm = generator(&INPUT);
-        assert_eq!(pm.obstacles.len(), 10);
-        assert_eq!(pm.obstacles[0][4], true);
-        assert_eq!(pm.obstacles[9][6], true);
-        assert_eq!(pm.guard_start, (4, 6));
-        assert_eq!(pm.guard_direction, (0, -1));
-        assert_eq!(pm.height, 10);
-        assert_eq!(pm.width, 10);
+        let pm = generator(EXAMPLE).unwrap();
+        assert_eq!(pm.obstacles[Point::new(4, 0)], true);
+        assert_eq!(pm.obstacles[Point::new(6, 9)], true);
+        assert_eq!(pm.guard_start, Point::new(4, 6));
+        assert_eq!(pm.guard_direction, Point::UP);
+        assert_eq!(pm.obstacles.height(), 10);
+        assert_eq!(pm.obstacles.width(), 10);
     }
 
     #[test]
     fn test_part_1() {
-        let pm = generator(&INPUT);
+        let pm = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&pm), 41);
     }
 
     #[test]
     fn test_part_2() {
-        let pm = generator(&INPUT);
+        let pm = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&pm), 6);
     }
-
-    #[test]
-    fn test_turn_right() {
-        let mut dir = (-1, 0);
-        turn_right(&mut dir);
-        assert_eq!(dir, (0, -1));
-        turn_right(&mut dir);
-        assert_eq!(dir, (1, 0));
-        turn_right(&mut dir);
-        assert_eq!(dir, (0, 1));
-        turn_right(&mut dir);
-        assert_eq!(dir, (-1, 0));
-    }
 }
@@ -1,6 +1,41 @@
+use crate::day::{self, Day, Example, ParseError};
 use std::iter::Iterator;
 use std::mem::swap;
 
+const EXAMPLE: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = Vec<Equation>;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("3749"), Some("11387")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+}
+
 pub fn part1(equations: &[Equation]) -> u64 {
     equations.iter()
         .filter_map(|e| if e.solve_simple { Some(e.value) } else { None }).sum()
@@ -17,7 +52,7 @@ pub fn part2(equations: &[Equation]) -> u64 {
         .sum()
 }
 
-pub fn generator(input: &str) -> Vec<Equation> {
+pub fn generator(input: &str) -> Result<Vec<Equation>, ParseError> {
     input.lines()
         .map(|l| Equation::new(l.trim()))
         .collect()
@@ -31,11 +66,11 @@ pub struct Equation {
 }
 
 impl Equation {
-    fn new(input: &str) -> Self {
-        let (value, operands) = input.split_once(": ").unwrap();
-        let value = value.parse().unwrap();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let (value, operands) = day::split_once(input, ": ")?;
+        let value = day::parse(value)?;
         let operands = operands.split(" ")
-            .map(|o| o.parse().unwrap()).collect();
+            .map(day::parse).collect::<Result<_, _>>()?;
 
         let mut e = Equation {
             value,
@@ -45,7 +80,7 @@ impl Equation {
         if e.solve::<false>() > 0 {
             e.solve_simple = true;
         }
-        e
+        Ok(e)
     }
 
     fn solve<const CONCATENATE:bool>(&self) -> usize {
@@ -97,53 +132,43 @@ impl Equation {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2, Equation};
-
-    const INPUT: &str = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
+    use super::{generator, part1, part2, Equation, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let equations = generator(&INPUT);
+        let equations = generator(EXAMPLE).unwrap();
         assert_eq!(9, equations.len());
     }
 
     #[test]
     fn test_part_1() {
-        let equations = generator(&INPUT);
+        let equations = generator(EXAMPLE).unwrap();
         assert_eq!(3749, part1(&equations));
     }
 
     #[test]
     fn test_part_2() {
-        let equations = generator(&INPUT);
+        let equations = generator(EXAMPLE).unwrap();
         assert_eq!(11387, part2(&equations));
     }
 
     #[test]
     fn test_solve() {
-        let e = Equation::new("161011: 16 10 13");
+        let e = Equation::new("161011: 16 10 13").unwrap();
         assert_eq!(0, e.solve::<false>());
 
-        let e = Equation::new("83: 17 5");
+        let e = Equation::new("83: 17 5").unwrap();
         assert_eq!(0, e.solve::<false>());
 
-        let e = Equation::new("77502824880: 9 574 9 6 8 8 7 2 26 8 5 3");
+        let e = Equation::new("77502824880: 9 574 9 6 8 8 7 2 26 8 5 3").unwrap();
         assert_eq!(0, e.solve::<false>());
     }
 
     #[test]
     fn test_concatenate() {
-        assert_eq!(0, Equation::new("83: 8 4").solve::<true>());
-        assert_eq!(false, Equation::new("161011: 16 10 13").solve::<true>() > 0);
-        assert_eq!(false, Equation::new("100: 1 0 0").solve::<false>() > 0);
-        assert_eq!(true, Equation::new("100: 1 0 0").solve::<true>() > 0);
+        assert_eq!(0, Equation::new("83: 8 4").unwrap().solve::<true>());
+        assert_eq!(false, Equation::new("161011: 16 10 13").unwrap().solve::<true>() > 0);
+        assert_eq!(false, Equation::new("100: 1 0 0").unwrap().solve::<false>() > 0);
+        assert_eq!(true, Equation::new("100: 1 0 0").unwrap().solve::<true>() > 0);
     }
 }
@@ -1,69 +1,76 @@
+use crate::day::{Day, Example, ParseError};
 use std::collections::{HashMap, HashSet};
-use std::iter::Iterator;
+use crate::utils::grid::{Grid, Point};
 
-pub fn part1(map: &CityMap) -> u32 {
-    map.find_antinodes::<false>().len() as u32
-}
+const EXAMPLE: &str = "............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............";
 
-pub fn part2(map: &CityMap) -> u32 {
-    map.find_antinodes::<true>().len() as u32
-}
+pub struct Solution;
 
-pub fn generator(input: &str) -> CityMap {
-    CityMap::new(input)
-}
+impl Day for Solution {
+    type Input = CityMap;
+    type Output1 = u32;
+    type Output2 = u32;
 
-#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
-struct XY {
-    x: i32,
-    y: i32,
-}
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("14"), Some("34")], params: &[] },
+    ];
 
-impl XY {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
     }
 
-    fn is_valid(&self, height: &i32, width: &i32) -> bool {
-        &self.x > &-1 && &self.x < width && &self.y > &-1 && &self.y < height
+    fn part1(input: &Self::Input) -> u32 {
+        part1(input)
     }
 
-    fn adjust(&mut self, dx: &i32, dy: &i32) {
-        self.x += dx;
-        self.y += dy;
+    fn part2(input: &Self::Input) -> u32 {
+        part2(input)
     }
 }
 
+pub fn part1(map: &CityMap) -> u32 {
+    map.find_antinodes::<false>().len() as u32
+}
+
+pub fn part2(map: &CityMap) -> u32 {
+    map.find_antinodes::<true>().len() as u32
+}
+
+pub fn generator(input: &str) -> Result<CityMap, ParseError> {
+    CityMap::new(input)
+}
+
 pub struct CityMap {
-    height: i32,
-    width: i32,
-    nodes: HashMap<char, Vec<XY>>,
+    grid: Grid<char>,
+    nodes: HashMap<char, Vec<Point>>,
 }
 
 impl CityMap {
-    fn new(input: &str) -> Self {
-        let mut height = 0i32;
-        let mut width = 0i32;
-        let mut nodes = HashMap::new();
-        input.lines().enumerate().for_each(|(y, line)| {
-            height += 1;
-            width = line.len() as i32;
-
-            line.chars().enumerate().filter(|(_, c)| c != &'.').for_each(|(x, c)| {
-                nodes.entry(c)
-                    .and_modify(|p: &mut Vec<XY>| p.push(XY::new(x as i32, y as i32)))
-                    .or_insert(vec![XY::new(x as i32, y as i32)]);
-            })
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(input, Some)?;
+        let mut nodes: HashMap<char, Vec<Point>> = HashMap::new();
+        grid.iter().filter(|(_, c)| **c != '.').for_each(|(p, c)| {
+            nodes.entry(*c).or_default().push(p);
         });
 
-        CityMap {
-            height,
-            width,
+        Ok(CityMap {
+            grid,
             nodes,
-        }
+        })
     }
 
-    fn find_antinodes<const RESONANT_HARMONICS:bool>(&self) -> HashSet<XY> {
+    fn find_antinodes<const RESONANT_HARMONICS:bool>(&self) -> HashSet<Point> {
         let mut antinodes = HashSet::new();
 
         self.nodes.iter().for_each(|(_, p)| {
@@ -80,27 +87,25 @@ impl CityMap {
         antinodes
     }
 
-    fn collect_anti_nodes_w_harmonics(&self, p1: &XY, p2: &XY, antinodes: &mut HashSet<XY>) {
-        antinodes.insert(p1.clone());
-        antinodes.insert(p2.clone());
+    fn collect_anti_nodes_w_harmonics(&self, p1: &Point, p2: &Point, antinodes: &mut HashSet<Point>) {
+        antinodes.insert(*p1);
+        antinodes.insert(*p2);
 
-        let dx = p1.x - p2.x;
-        let dy = p1.y - p2.y;
+        let d = *p1 - *p2;
         for (sgn, p) in [(1, p1), (-1, p2)] {
-            let mut xy = XY::new(p.x + (sgn * dx), p.y + (sgn * dy));
-            while xy.is_valid(&self.height, &self.width) {
+            let mut xy = *p + d * sgn;
+            while self.grid.contains(xy) {
                 antinodes.insert(xy);
-                xy.adjust(&(sgn * dx), &(sgn * dy));
+                xy += d * sgn;
             }
         }
     }
 
-    fn collect_anti_nodes(&self, p1: &XY, p2: &XY, antinodes: &mut HashSet<XY>) {
-        let dx = p1.x - p2.x;
-        let dy = p1.y - p2.y;
+    fn collect_anti_nodes(&self, p1: &Point, p2: &Point, antinodes: &mut HashSet<Point>) {
+        let d = *p1 - *p2;
 
-        for xy in [XY::new(p1.x + dx, p1.y + dy), XY::new(p2.x - dx, p2.y - dy)] {
-            if xy.is_valid(&self.height, &self.width) {
+        for xy in [*p1 + d, *p2 - d] {
+            if self.grid.contains(xy) {
                 antinodes.insert(xy);
             }
         }
@@ -109,44 +114,31 @@ impl CityMap {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2, XY};
-
-    const INPUT: &str = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
+    use super::{generator, part1, part2, Point, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let map = generator(&INPUT);
-        assert_eq!(map.width, 12);
-        assert_eq!(map.height, 12);
+        let map = generator(EXAMPLE).unwrap();
+        assert_eq!(map.grid.width(), 12);
+        assert_eq!(map.grid.height(), 12);
         assert_eq!(map.nodes.len(), 2);
         assert_eq!(map.nodes.get(&'A').unwrap(),
                    &vec![
-                       XY::new(6, 5),
-                       XY::new(8, 8),
-                       XY::new(9, 9),
+                       Point::new(6, 5),
+                       Point::new(8, 8),
+                       Point::new(9, 9),
                    ]);
     }
 
     #[test]
     fn test_part_1() {
-        let map = generator(&INPUT);
+        let map = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&map), 14);
     }
 
     #[test]
     fn test_part_2() {
-        let map = generator(&INPUT);
+        let map = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&map), 34);
     }
 }
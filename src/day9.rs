@@ -1,5 +1,32 @@
+use crate::day::{Day, Example, ParseError};
 use std::iter::Iterator;
 
+const EXAMPLE: &str = "2333133121414131402";
+
+pub struct Solution;
+
+impl Day for Solution {
+    type Input = DiskMap;
+    type Output1 = u64;
+    type Output2 = u64;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example { input: EXAMPLE, answers: [Some("1928"), Some("2858")], params: &[] },
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {
+        generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> u64 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> u64 {
+        part2(input)
+    }
+}
+
 pub fn part1(disk_map: &DiskMap) -> u64 {
     disk_map.compact(false)
 }
@@ -8,7 +35,7 @@ pub fn part2(disk_map: &DiskMap) -> u64 {
     disk_map.compact(true)
 }
 
-pub fn generator(input: &str) -> DiskMap {
+pub fn generator(input: &str) -> Result<DiskMap, ParseError> {
     DiskMap::new(input)
 }
 
@@ -53,29 +80,32 @@ pub struct DiskMap {
 }
 
 impl DiskMap {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut blocks = Vec::new();
         let mut free = Vec::new();
-        let chars: Vec<char> = input.lines().next().unwrap().chars().collect();
+        let line = input.lines().next().ok_or_else(|| ParseError::missing(input, "disk map"))?;
+        let sizes: Vec<u32> = line.char_indices()
+            .map(|(i, c)| c.to_digit(10).ok_or_else(|| ParseError::unexpected(&line[i..])))
+            .collect::<Result<_, _>>()?;
         let mut pos = 0;
         let mut id = 0;
-        chars.chunks(2).for_each(|chunk| {
-            blocks.push(Block { id, pos, size: chunk[0].to_digit(10).unwrap() });
+        sizes.chunks(2).for_each(|chunk| {
+            blocks.push(Block { id, pos, size: chunk[0] });
             id += 1;
             pos += blocks.last().unwrap().size;
             if chunk.len() == 2 {
-                free.push(Space { pos, size: chunk[1].to_digit(10).unwrap() });
+                free.push(Space { pos, size: chunk[1] });
                 pos += free.last().unwrap().size;
             }
         });
         if blocks.len() != free.len() + 1 {
-            panic!("invalid input");
+            return Err(ParseError::invalid(&line[line.len()..], "disk map must end with a file"));
         }
 
-        DiskMap {
+        Ok(DiskMap {
             blocks,
             free,
-        }
+        })
     }
 
     fn compact(&self, full: bool) -> u64 {
@@ -116,26 +146,24 @@ impl DiskMap {
 
 #[cfg(test)]
 mod tests {
-    use super::{generator, part1, part2};
-
-    const INPUT: &str = "2333133121414131402";
+    use super::{generator, part1, part2, EXAMPLE};
 
     #[test]
     fn test_generator() {
-        let map = generator(&INPUT);
+        let map = generator(EXAMPLE).unwrap();
         assert_eq!(map.blocks.len(), 10);
         assert_eq!(map.free.len(), 9);
     }
 
     #[test]
     fn test_part_1() {
-        let map = generator(&INPUT);
+        let map = generator(EXAMPLE).unwrap();
         assert_eq!(part1(&map), 1928);
     }
 
     #[test]
     fn test_part_2() {
-        let map = generator(&INPUT);
+        let map = generator(EXAMPLE).unwrap();
         assert_eq!(part2(&map), 2858);
     }
 }
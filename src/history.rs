@@ -0,0 +1,177 @@
+use crate::report::nanos;
+use crate::utils::{DayResult, Outcome};
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time;
+
+/// How many of the most recent runs make up the baseline of a part.
+pub const BASELINE_RUNS: usize = 5;
+
+/// The timings of one run, stored as a line of JSON in the history file.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Entry {
+  /// Seconds since the Unix epoch
+  pub timestamp: u64,
+  /// The git revision of the code, if it could be found
+  pub revision: Option<String>,
+  /// The build profile, since debug timings don't compare with release ones
+  pub profile: String,
+  /// Map from day name to phase (generator, part1 or part2) to nanoseconds
+  pub days: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl Entry {
+  /// Record the median times of the days that ran successfully.
+  pub fn new(results: &[DayResult], revision: Option<String>) -> Self {
+    let timestamp = time::SystemTime::now().duration_since(time::UNIX_EPOCH)
+      .map(|d| d.as_secs()).unwrap_or(0);
+    let days = results.iter()
+      .filter(|r| r.outcome == Outcome::Ok)
+      .map(|r| {
+        let mut phases = BTreeMap::new();
+        phases.insert("generator".to_string(), nanos(r.generate_time.median()));
+        for (i, part) in [&r.part1, &r.part2].iter().enumerate() {
+          if let Some((timing, _)) = part {
+            phases.insert(format!("part{}", i + 1), nanos(timing.median()));
+          }
+        }
+        (r.day.clone(), phases)
+      })
+      .collect();
+    Entry { timestamp, revision, profile: profile().to_string(), days }
+  }
+}
+
+/// The build profile that this binary was compiled with.
+pub fn profile() -> &'static str {
+  if cfg!(debug_assertions) { "debug" } else { "release" }
+}
+
+/// The short git revision of the working directory, with a "-dirty" suffix
+/// when there are uncommitted changes.
+pub fn git_revision() -> Option<String> {
+  let git = |args: &[&str]| std::process::Command::new("git").args(args).output().ok()
+    .filter(|out| out.status.success())
+    .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string());
+  let revision = git(&["rev-parse", "--short", "HEAD"])?;
+  match git(&["status", "--porcelain", "--untracked-files=no"]) {
+    Some(changes) if !changes.is_empty() => Some(format!("{revision}-dirty")),
+    _ => Some(revision),
+  }
+}
+
+pub fn make_filename(directory: &str) -> String {
+  Path::new(directory).join("history.jsonl").to_string_lossy().to_string()
+}
+
+/// Read the history of the runs against an input directory, oldest first.
+/// Lines that can't be parsed are skipped so that a damaged file doesn't
+/// stop the run.
+pub fn read(directory: &str) -> Vec<Entry> {
+  fs::read_to_string(make_filename(directory))
+    .map(|text| text.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    .unwrap_or_default()
+}
+
+/// Add an entry to the end of an input directory's history.
+pub fn append(directory: &str, entry: &Entry) -> io::Result<()> {
+  let mut f = fs::OpenOptions::new()
+    .append(true)
+    .create(true)
+    .open(make_filename(directory))?;
+  writeln!(f, "{}", serde_json::to_string(entry).expect("Can't serialize history"))
+}
+
+/// The median time of a phase over the last BASELINE_RUNS runs that have it
+/// in the same profile, or None if it never ran before.
+pub fn baseline(history: &[Entry], profile: &str, day: &str, phase: &str) -> Option<u64> {
+  let mut recent: Vec<u64> = history.iter().rev()
+    .filter(|e| e.profile == profile)
+    .filter_map(|e| e.days.get(day).and_then(|phases| phases.get(phase)).copied())
+    .take(BASELINE_RUNS)
+    .collect();
+  recent.sort();
+  match recent.len() {
+    0 => None,
+    n if n % 2 == 0 => Some((recent[n / 2 - 1] + recent[n / 2]) / 2),
+    n => Some(recent[n / 2]),
+  }
+}
+
+/// A phase of a day that got slower than its baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+  pub day: String,
+  pub phase: String,
+  pub baseline_ns: u64,
+  pub current_ns: u64,
+}
+
+impl Regression {
+  /// How much slower the current run is, in percent of the baseline.
+  pub fn slowdown(&self) -> f64 {
+    (self.current_ns as f64 / self.baseline_ns as f64 - 1.0) * 100.0
+  }
+}
+
+/// Find the phases of the current run that are more than threshold percent
+/// slower than their baseline in the history.
+pub fn compare(history: &[Entry], current: &Entry, threshold: f64) -> Vec<Regression> {
+  let mut regressions = Vec::new();
+  for (day, phases) in &current.days {
+    for (phase, &current_ns) in phases {
+      let Some(baseline_ns) = baseline(history, &current.profile, day, phase) else { continue };
+      if current_ns as f64 > baseline_ns as f64 * (1.0 + threshold / 100.0) {
+        regressions.push(Regression { day: day.clone(), phase: phase.clone(), baseline_ns,
+                                      current_ns });
+      }
+    }
+  }
+  regressions
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{baseline, compare, Entry, Regression};
+  use std::collections::BTreeMap;
+
+  fn entry(profile: &str, day1: &[(&str, u64)]) -> Entry {
+    let phases = day1.iter().map(|(p, ns)| (p.to_string(), *ns)).collect();
+    Entry { timestamp: 0, revision: None, profile: profile.to_string(),
+            days: BTreeMap::from([("day1".to_string(), phases)]) }
+  }
+
+  #[test]
+  fn test_baseline() {
+    let history: Vec<Entry> = [900, 100, 120, 110, 130, 140].iter()
+      .map(|&ns| entry("release", &[("part1", ns)]))
+      .chain([entry("debug", &[("part1", 5000), ("part2", 10)])])
+      .collect();
+    // Only the last five release runs count, so the slow first one drops out
+    assert_eq!(Some(120), baseline(&history, "release", "day1", "part1"));
+    assert_eq!(None, baseline(&history, "release", "day1", "part2"));
+    assert_eq!(Some(10), baseline(&history, "debug", "day1", "part2"));
+    assert_eq!(None, baseline(&history, "release", "day2", "part1"));
+  }
+
+  #[test]
+  fn test_compare() {
+    let history = vec![entry("release", &[("generator", 100), ("part1", 100)])];
+    let current = entry("release", &[("generator", 109), ("part1", 150), ("part2", 900)]);
+    let regressions = compare(&history, &current, 10.0);
+    assert_eq!(vec![Regression { day: "day1".to_string(), phase: "part1".to_string(),
+                                 baseline_ns: 100, current_ns: 150 }], regressions);
+    assert_eq!(50.0, regressions[0].slowdown());
+  }
+
+  #[test]
+  fn test_round_trip() {
+    let e = entry("release", &[("part1", 42)]);
+    let line = serde_json::to_string(&e).unwrap();
+    assert_eq!(e, serde_json::from_str::<Entry>(&line).unwrap());
+  }
+}
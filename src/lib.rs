@@ -1,5 +1,14 @@
 extern crate core;
 
+pub mod alloc;
+pub mod answer;
+pub mod day;
+pub mod history;
+pub mod params;
+pub mod report;
+pub mod scaffold;
+pub mod select;
 pub mod utils;
+pub mod watch;
 
 day_list!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23);
\ No newline at end of file
@@ -1,28 +1,91 @@
-use aoc2024::{FUNCS,NAMES,utils};
+use aoc2024::{EXAMPLES,FUNCS,NAMES,PARAMS,history,params,report,select,utils,watch};
+use aoc2024::answer::Answer;
 
 use argh::FromArgs;
 use colored::Colorize;
 use serde::{Deserialize,Serialize};
 use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::{Read, Write};
 use std::path::Path;
+use std::time::Duration;
 
 #[derive(FromArgs)]
 /** Solution for Advent of Code (https://adventofcode.com/)*/
 struct Args {
-    /// the input directory
-    #[argh(option, short='i', default="String::from(\"input\")")]
-    input: String,
+    /// the input directory, or - to read a single day's input from stdin;
+    /// repeat it to run against several input sets (defaults to input)
+    #[argh(option, short='i')]
+    input: Vec<String>,
 
-    /// a single day to execute (defaults to all)
+    /// run against every subdirectory that has an answers.yml
+    #[argh(switch)]
+    all_inputs: bool,
+
+    /// check the results against answers.yml and fail on any mismatch
+    #[argh(switch)]
+    check: bool,
+
+    /// record the results as the new answers in answers.yml
+    #[argh(switch)]
+    accept: bool,
+
+    /// the output format: text, json, csv, markdown or html (defaults to text)
+    #[argh(option, default="report::Format::Text")]
+    format: report::Format,
+
+    /// the number of days to run at the same time (defaults to 1)
+    #[argh(option, short='j', default="1")]
+    jobs: usize,
+
+    /// the number of timed runs of each day, which must all give the same
+    /// answers; the generator's results aren't compared (defaults to 1)
+    #[argh(option, default="1")]
+    repeat: usize,
+
+    /// the number of untimed runs of each day before the timed ones
+    #[argh(option, default="0")]
+    warmup: usize,
+
+    /// flag the parts that are more than this many percent slower than the
+    /// median of their last runs in history.jsonl
+    #[argh(option)]
+    compare: Option<f64>,
+
+    /// the number of seconds each phase of a day may run before it is
+    /// abandoned (defaults to no limit)
+    #[argh(option)]
+    timeout: Option<f64>,
+
+    /// run the days on the examples from their puzzles and check the answers
+    #[argh(switch)]
+    example: bool,
+
+    /// set a parameter of a day, such as day14.width=11, over its default
+    /// and the input directory's params.yml; repeat it to set several
+    #[argh(option)]
+    param: Vec<String>,
+
+    /// keep running the selected days on the input directory, running them
+    /// again whenever their input, answers.yml or params.yml changes
+    #[argh(switch)]
+    watch: bool,
+
+    /// run only the last implemented day
+    #[argh(switch)]
+    last: bool,
+
+    /// the days to execute, such as 3, 3-7, 1,5,9 or 12:2 for only part 2 of
+    /// day 12 (defaults to all)
     #[argh(positional)]
-    day: Option<usize>,
+    days: Vec<String>,
 }
 
-#[derive(Default,Deserialize,Serialize)]
+#[derive(Clone,Default,Deserialize,Serialize)]
 struct Answers {
-    // map from day name to answers
-    days: BTreeMap<String,Vec<String>>,
+    // map from day name to answers, which are null for parts that never ran;
+    // numeric answers are stored as numbers and only match numbers
+    days: BTreeMap<String,Vec<Option<Answer>>>,
 }
 
 impl Answers {
@@ -38,19 +101,62 @@ impl Answers {
         }
     }
 
-    fn update(&mut self, delta_list: &Vec<utils::DayResult>) {
-        for delta in delta_list {
-            let new_val = delta.get_answers();
-            if let Some(prev) =
-              self.days.insert(delta.day.to_string(), new_val.clone()) {
-                if prev != new_val {
-                    println!("{}", format!("Output for {} changed from {:?} to {:?}!",
-                                           delta.pretty_day(), prev, new_val).bold());
+    /// Record the answers of the parts that ran, keeping the others.
+    fn update(&mut self, delta_list: &[utils::DayResult], log: &mut dyn Write) {
+        for delta in delta_list.iter().filter(|d| d.outcome == utils::Outcome::Ok) {
+            let answers = self.days.entry(delta.day.to_string()).or_default();
+            for (i, new_val) in delta.get_answers().into_iter().enumerate() {
+                let Some(new_val) = new_val else { continue };
+                if answers.len() <= i {
+                    answers.resize(i + 1, None);
+                }
+                if let Some(prev) = answers[i].replace(new_val.clone()) {
+                    if prev != new_val {
+                        writeln!(log, "{}", format!("Output for {} part {} changed from {} to {}!",
+                                                    delta.pretty_day(), i + 1, prev.literal(),
+                                                    new_val.literal()).bold())
+                          .unwrap();
+                    }
                 }
             }
         }
     }
 
+    /// Print a table of how each part compares with the known answers.
+    /// Returns true if every part that has a known answer matches it.
+    fn check(&self, results: &[utils::DayResult], log: &mut dyn Write) -> bool {
+        let mut passed = true;
+        let width = results.iter().map(|r| r.pretty_day().len()).max().unwrap_or(0).max(8);
+        writeln!(log, "{}", format!("{:width$} {:8} {:8}", "Day", "Part 1", "Part 2").bold())
+          .unwrap();
+        for r in results {
+            let status = r.check(self.days.get(&r.day));
+            writeln!(log, "{:width$} {:8} {:8}", r.pretty_day(), status[0], status[1]).unwrap();
+            passed &= self.explain(r, &status, log);
+        }
+        passed
+    }
+
+    /// Print why each failing part of a day failed. Returns true if none did.
+    fn explain(&self, r: &utils::DayResult, status: &[utils::Status], log: &mut dyn Write) -> bool {
+        let mut passed = true;
+        for (i, s) in status.iter().enumerate() {
+            if *s == utils::Status::Fail {
+                passed = false;
+                match &r.outcome {
+                    utils::Outcome::Ok =>
+                        writeln!(log, "  - {} part {} expected {} but got {}", r.pretty_day(),
+                                 i + 1, self.days[&r.day][i].as_ref().unwrap().literal(),
+                                 r.get_answers()[i].as_ref().unwrap().literal()).unwrap(),
+                    failure =>
+                        writeln!(log, "  - {} part {} didn't run: {}", r.pretty_day(), i + 1,
+                                 failure).unwrap(),
+                }
+            }
+        }
+        passed
+    }
+
     fn write(&self, directory: &str) {
         let f = std::fs::OpenOptions::new()
           .write(true)
@@ -64,33 +170,401 @@ impl Answers {
 
 fn main() {
     let args: Args = argh::from_env();
-    // Did the user pick a single day to run
-    let day_filter: Option<usize> = match args.day {
-        Some(day) => {
-            let name = format!("day{}", day);
-            Some(NAMES.iter().position(|x| **x == name)
-              .expect("Requested an unimplemented day"))
-        },
-        None => None
+    if args.check && args.accept {
+        eprintln!("--check and --accept can't be used together");
+        std::process::exit(2);
+    }
+    if args.last && !args.days.is_empty() {
+        eprintln!("--last can't be used with a list of days");
+        std::process::exit(2);
+    }
+    if args.timeout.is_some_and(|t| !t.is_finite() || t <= 0.0) {
+        eprintln!("--timeout must be a positive number of seconds");
+        std::process::exit(2);
+    }
+    if args.compare.is_some_and(|c| !c.is_finite() || c < 0.0) {
+        eprintln!("--compare must be a percentage of at least 0");
+        std::process::exit(2);
+    }
+    if args.repeat == 0 {
+        eprintln!("--repeat must be at least 1");
+        std::process::exit(2);
+    }
+    let mut cli_params = params::Settings::default();
+    for assignment in &args.param {
+        if let Err(e) = cli_params.set(assignment)
+            .and_then(|_| cli_params.validate(NAMES, PARAMS)) {
+            eprintln!("--param: {e}");
+            std::process::exit(2);
+        }
+    }
+    // Did the user pick the days and parts to run
+    let selected = if args.last {
+        vec![select::Selection::all(NAMES.len() - 1)]
+    } else {
+        select::parse_selectors(&args.days, NAMES).unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        })
+    };
+    // Keep stdout clean for the machine-readable formats
+    let text = args.format == report::Format::Text;
+    let mut log: Box<dyn Write> = if text {
+        Box::new(std::io::stdout())
+    } else {
+        Box::new(std::io::stderr())
+    };
+    let options = utils::RunOptions { repeat: args.repeat, warmup: args.warmup,
+                                      timeout: args.timeout.map(Duration::from_secs_f64),
+                                      ..utils::RunOptions::default() };
+    if args.example {
+        if !args.input.is_empty() || args.all_inputs || args.accept || args.compare.is_some()
+            || args.watch {
+            eprintln!("--example can't be used with -i, --all-inputs, --accept, --compare or \
+                       --watch");
+            std::process::exit(2);
+        }
+        if !run_examples(&args, &selected, &options, &cli_params, &mut log) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    let inputs = if args.all_inputs {
+        if !args.input.is_empty() {
+            eprintln!("--all-inputs can't be used with -i");
+            std::process::exit(2);
+        }
+        let found = discover_inputs();
+        if found.is_empty() {
+            eprintln!("No directory with an answers.yml was found");
+            std::process::exit(2);
+        }
+        found
+    } else if args.input.is_empty() {
+        vec![String::from("input")]
+    } else {
+        args.input.clone()
+    };
+    if args.watch {
+        if inputs.len() != 1 || inputs[0] == "-" || args.accept || args.compare.is_some() || !text {
+            eprintln!("--watch needs a single input directory and can't be used with --accept, \
+                       --compare or --format");
+            std::process::exit(2);
+        }
+        watch(&args, &inputs[0], &selected, &options, &cli_params, &mut log);
+    }
+    // With stdin there is only one input, so there can only be one day
+    let stdin = if inputs.iter().any(|i| i == "-") {
+        if inputs.len() != 1 {
+            eprintln!("Reading from stdin can't be combined with other inputs");
+            std::process::exit(2);
+        }
+        if selected.len() != 1 {
+            eprintln!("Reading from stdin needs exactly one day");
+            std::process::exit(2);
+        }
+        if args.accept || args.compare.is_some() {
+            eprintln!("--accept and --compare need an input directory");
+            std::process::exit(2);
+        }
+        let mut data = String::new();
+        std::io::stdin().read_to_string(&mut data).expect("Can't read stdin");
+        Some(data)
+    } else {
+        None
     };
-    // Read the inputs from the given directory
-    println!("{} {}\n", "Reading from".bold(), &args.input);
-    let inputs = utils::read_inputs(&args.input, NAMES)
-      .expect("Can't read input dir");
 
+    let revision = history::git_revision();
+    let mut passed = true;
+    let mut runs = Vec::new();
+    for input in &inputs {
+        if !runs.is_empty() {
+            writeln!(log).unwrap();
+        }
+        writeln!(log, "{} {}\n", "Reading from".bold(), input).unwrap();
+        let settings = match &stdin {
+            Some(_) => Ok(cli_params.clone()),
+            None => read_settings(input, &cli_params),
+        }.unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        });
+        let (elapsed, results) = run_input(&args, &selected, input, &stdin, &settings, &options);
+        let summed: Duration = results.iter().map(|r| r.total_time()).sum();
+
+        if text {
+            print_results(&results, elapsed);
+        }
+
+        let mut old_answers = Answers::read(input);
+        if args.check {
+            // Several inputs are checked together in the matrix below
+            if inputs.len() == 1 {
+                writeln!(log).unwrap();
+                passed = old_answers.check(&results, &mut log);
+            }
+        } else if args.accept {
+            old_answers.update(&results, &mut log);
+            old_answers.write(input);
+        } else {
+            // Only point out the differences, the answers are left untouched
+            old_answers.clone().update(&results, &mut log);
+        }
+
+        // Keep the timings of every run against a directory in its history
+        if stdin.is_none() {
+            let entry = history::Entry::new(&results, revision.clone());
+            if let Some(threshold) = args.compare {
+                let regressions = history::compare(&history::read(input), &entry, threshold);
+                report_regressions(&regressions, threshold, &mut log);
+            }
+            if let Err(e) = history::append(input, &entry) {
+                writeln!(log, "Can't record the timings in {}: {e}",
+                         history::make_filename(input)).unwrap();
+            }
+        }
+        let checks: Vec<_> = results.iter().map(|r| r.check(old_answers.days.get(&r.day))).collect();
+        let report = report::Report::new(input, elapsed, summed, &results).with_checks(&checks);
+        runs.push((report, results, old_answers));
+    }
+
+    match args.format {
+        report::Format::Text => {}
+        report::Format::Json if runs.len() == 1 => println!("{}", runs[0].0.to_json()),
+        report::Format::Json => {
+            let reports: Vec<&report::Report> = runs.iter().map(|(r, _, _)| r).collect();
+            println!("{}", serde_json::to_string_pretty(&reports).expect("Can't serialize report"));
+        }
+        report::Format::Csv => {
+            print!("{}", report::CSV_HEADER);
+            for (r, _, _) in &runs {
+                print!("{}", r.csv_rows());
+            }
+        }
+        report::Format::Markdown => {
+            let tables: Vec<String> = runs.iter().map(|(r, _, _)| r.to_markdown()).collect();
+            print!("{}", tables.join("\n"));
+        }
+        report::Format::Html => {
+            let reports: Vec<&report::Report> = runs.iter().map(|(r, _, _)| r).collect();
+            print!("{}", report::to_html(&reports));
+        }
+    }
+
+    if runs.len() > 1 {
+        writeln!(log).unwrap();
+        passed &= cross_check(&runs, &mut log);
+    }
+    if args.check && !passed {
+        std::process::exit(1);
+    }
+}
+
+/// The parameters for an input directory: its params.yml, over which the
+/// command line takes precedence.
+fn read_settings(input: &str, cli_params: &params::Settings) -> Result<params::Settings, String> {
+    let settings = params::Settings::read(input)?;
+    settings.validate(NAMES, PARAMS)
+        .map_err(|e| format!("{}: {e}", params::Settings::make_filename(input)))?;
+    Ok(settings.with(cli_params))
+}
+
+/// Run the selected days on an input directory, or on the data from stdin.
+fn run_input(args: &Args, selected: &[select::Selection], input: &str, stdin: &Option<String>,
+             settings: &params::Settings, options: &utils::RunOptions)
+             -> (Duration, Vec<utils::DayResult>) {
+    utils::time(&|| {
+        utils::parallel_map(args.jobs, selected, |s| {
+            let day = NAMES[s.index];
+            // Only read the inputs of the selected days
+            let (path, data) = match stdin {
+                Some(data) => ("-".to_string(), Ok(data.clone())),
+                None => {
+                    let path = utils::input_path(input, day);
+                    let data = std::fs::read_to_string(&path);
+                    (path, data)
+                }
+            };
+            match data {
+                Ok(data) => FUNCS[s.index](&data, &utils::RunOptions {
+                    parts: s.parts,
+                    params: settings.for_day(day),
+                    ..options.clone()
+                }),
+                Err(e) => utils::DayResult::without_input(day, &path, &e.to_string()),
+            }
+        })
+    })
+}
+
+fn print_results(results: &[utils::DayResult], elapsed: Duration) {
+    let summed: Duration = results.iter().map(|r| r.total_time()).sum();
+    for r in results {
+        println!("{}", r);
+    }
+    println!("{} {} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed(),
+             format!("[sum of days {:.2?}]", summed).dimmed());
+}
+
+/// Run the selected days on an input directory, and again every time one of
+/// the files they read changes, showing how their answers and timings moved
+/// since the previous run. Runs until interrupted.
+fn watch(args: &Args, input: &str, selected: &[select::Selection], options: &utils::RunOptions,
+         cli_params: &params::Settings, log: &mut dyn Write) -> ! {
+    const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+    let mut paths: Vec<String> = selected.iter()
+        .map(|s| utils::input_path(input, NAMES[s.index]))
+        .collect();
+    paths.dedup();
+    paths.push(Answers::make_filename(input));
+    paths.push(params::Settings::make_filename(input));
+    let mut snapshot = watch::Snapshot::take(&paths);
+    let mut previous: Option<Vec<utils::DayResult>> = None;
+    writeln!(log, "{} {}\n", "Reading from".bold(), input).unwrap();
+    loop {
+        // A broken params.yml is reported and waited out like any other change
+        match read_settings(input, cli_params) {
+            Err(e) => writeln!(log, "{e}").unwrap(),
+            Ok(settings) => {
+                let (elapsed, results) = run_input(args, selected, input, &None, &settings, options);
+                print_results(&results, elapsed);
+                let answers = Answers::read(input);
+                writeln!(log).unwrap();
+                if args.check {
+                    answers.check(&results, log);
+                } else {
+                    answers.clone().update(&results, log);
+                }
+                if let Some(previous) = &previous {
+                    writeln!(log, "\n{}", "Since the last run".bold()).unwrap();
+                    for line in watch::differences(previous, &results) {
+                        writeln!(log, " · {line}").unwrap();
+                    }
+                }
+                previous = Some(results);
+            }
+        }
+        writeln!(log, "\n{} {} {}", "Watching".bold(), paths.join(", "),
+                 "(Ctrl-C to stop)".dimmed()).unwrap();
+        let (changed, next) = watch::wait_for_change(&paths, &snapshot, POLL_INTERVAL);
+        snapshot = next;
+        writeln!(log, "\n{} {}\n", "Changed".bold(), changed.join(", ")).unwrap();
+    }
+}
+
+/// Run the selected days on each of their examples and check the answers.
+/// Returns true if none of them failed.
+fn run_examples(args: &Args, selected: &[select::Selection], options: &utils::RunOptions,
+                settings: &params::Settings, log: &mut dyn Write) -> bool {
+    let mut examples = Vec::new();
+    for s in selected {
+        if EXAMPLES[s.index].is_empty() {
+            writeln!(log, "{} has no examples", NAMES[s.index]).unwrap();
+        }
+        examples.extend((0..EXAMPLES[s.index].len()).map(|k| (s, k)));
+    }
+    writeln!(log, "{} the examples\n", "Reading from".bold()).unwrap();
     let (elapsed, results) = utils::time(&|| {
-        FUNCS.iter().enumerate()
-          .filter(|(p, _)| day_filter.is_none() || day_filter.unwrap() == *p)
-          .map(|(p, f)| f(&inputs[p]))
-          .collect::<Vec<utils::DayResult>>()
+        utils::parallel_map(args.jobs, &examples, |(s, k)| {
+            let mut result = utils::run_example(FUNCS[s.index], &EXAMPLES[s.index][*k],
+                                                &utils::RunOptions {
+                                                    parts: s.parts,
+                                                    params: settings.for_day(NAMES[s.index]),
+                                                    ..options.clone()
+                                                });
+            result.day = format!("{} example {}", NAMES[s.index], k + 1);
+            result
+        })
     });
+    let summed: Duration = results.iter().map(|r| r.total_time()).sum();
+    // The examples' answers take the place of answers.yml
+    let answers = Answers {
+        days: examples.iter().zip(&results)
+          .map(|((s, k), r)| (r.day.clone(), EXAMPLES[s.index][*k].expected()))
+          .collect(),
+    };
+    let checks: Vec<_> = results.iter().map(|r| r.check(answers.days.get(&r.day))).collect();
+    let report = report::Report::new("examples", elapsed, summed, &results).with_checks(&checks);
+    match args.format {
+        report::Format::Text => {
+            for r in &results {
+                println!("{}", r);
+            }
+            println!("{} {} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed(),
+                     format!("[sum of days {:.2?}]", summed).dimmed());
+        }
+        report::Format::Json => println!("{}", report.to_json()),
+        report::Format::Csv => print!("{}", report.to_csv()),
+        report::Format::Markdown => print!("{}", report.to_markdown()),
+        report::Format::Html => print!("{}", report::to_html(&[&report])),
+    }
 
-    for r in &results {
-        println!("{}", r);
+    writeln!(log).unwrap();
+    answers.check(&results, log)
+}
+
+/// Print the parts that got slower than their baseline.
+fn report_regressions(regressions: &[history::Regression], threshold: f64, log: &mut dyn Write) {
+    writeln!(log).unwrap();
+    if regressions.is_empty() {
+        writeln!(log, "{}", format!("Nothing is more than {threshold}% slower than its baseline")
+          .green()).unwrap();
+        return;
     }
-    println!("{} {}", "Overall runtime".bold(), format!("({:.2?})", elapsed).dimmed());
+    writeln!(log, "{}", format!("Slower than the median of the last {} runs by more than {}%",
+                                history::BASELINE_RUNS, threshold).red().bold()).unwrap();
+    for r in regressions {
+        writeln!(log, "  - {} {}: {:.2?} vs {:.2?} ({:.1}% slower)", r.day,
+                 r.phase.replace("part", "part "), Duration::from_nanos(r.current_ns),
+                 Duration::from_nanos(r.baseline_ns), r.slowdown()).unwrap();
+    }
+}
 
-    let mut old_answers = Answers::read(&args.input);
-    old_answers.update(&results);
-    old_answers.write(&args.input);
-}
\ No newline at end of file
+/// The subdirectories of the current directory that have an answers.yml,
+/// each of which is a set of inputs with its known-good answers.
+fn discover_inputs() -> Vec<String> {
+    let mut found: Vec<String> = std::fs::read_dir(".").expect("Can't list the current directory")
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("answers.yml").is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    found.sort();
+    found
+}
+
+/// Print a matrix of how each day did against each input's answers, and
+/// why any of them failed. Returns true if none of them failed.
+fn cross_check(runs: &[(report::Report, Vec<utils::DayResult>, Answers)],
+               log: &mut dyn Write) -> bool {
+    let widths: Vec<usize> = runs.iter().map(|(r, _, _)| r.input.len().max(8)).collect();
+    write!(log, "{}", format!("{:8}", "Day").bold()).unwrap();
+    for ((r, _, _), width) in runs.iter().zip(&widths) {
+        write!(log, " {}", format!("{:width$}", r.input).bold()).unwrap();
+    }
+    writeln!(log).unwrap();
+    for (i, day) in runs[0].1.iter().enumerate() {
+        write!(log, "{:8}", day.pretty_day()).unwrap();
+        for ((_, results, answers), width) in runs.iter().zip(&widths) {
+            let r = &results[i];
+            let status = utils::Status::summarize(&r.check(answers.days.get(&r.day)));
+            write!(log, " {:width$}", status).unwrap();
+        }
+        writeln!(log).unwrap();
+    }
+    let mut passed = true;
+    for (report, results, answers) in runs {
+        let mut failed = results.iter()
+          .map(|r| (r, r.check(answers.days.get(&r.day))))
+          .filter(|(_, status)| status.contains(&utils::Status::Fail))
+          .peekable();
+        if failed.peek().is_some() {
+            passed = false;
+            writeln!(log, "\n{}", format!("Failures in {}", report.input).bold()).unwrap();
+        }
+        for (r, status) in failed {
+            answers.explain(r, &status, log);
+        }
+    }
+    passed
+}
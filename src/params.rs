@@ -0,0 +1,188 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// A parameter that a day declares: its name, its value in the real puzzle,
+/// and the values that the day can run with.
+pub type Param = (&'static str, i64, RangeInclusive<i64>);
+
+/// The named parameters of a day.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Params {
+  values: BTreeMap<String, i64>,
+}
+
+impl Params {
+  /// The value of a parameter. Every parameter that a day declares has a
+  /// value, so asking for any other is a bug in the day.
+  pub fn get(&self, name: &str) -> i64 {
+    *self.values.get(name).unwrap_or_else(|| panic!("unknown parameter {name}"))
+  }
+
+  pub fn set(&mut self, name: &str, value: i64) {
+    self.values.insert(name.to_string(), value);
+  }
+
+  /// These parameters with the ones in overrides replacing them.
+  pub fn with(&self, overrides: &Params) -> Params {
+    let mut values = self.values.clone();
+    values.extend(overrides.values.iter().map(|(name, value)| (name.clone(), *value)));
+    Params { values }
+  }
+}
+
+impl From<&[(&str, i64)]> for Params {
+  fn from(pairs: &[(&str, i64)]) -> Self {
+    Params { values: pairs.iter().map(|(name, value)| (name.to_string(), *value)).collect() }
+  }
+}
+
+impl From<&[Param]> for Params {
+  /// The default values of the declared parameters.
+  fn from(declared: &[Param]) -> Self {
+    Params { values: declared.iter().map(|(name, value, _)| (name.to_string(), *value)).collect() }
+  }
+}
+
+/// The parameters that were set for each day, such as with
+/// `--param day14.width=11` or in an input directory's params.yml:
+///
+/// ```yaml
+/// day14:
+///   width: 11
+///   height: 7
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(transparent)]
+pub struct Settings {
+  days: BTreeMap<String, BTreeMap<String, i64>>,
+}
+
+impl Settings {
+  pub fn make_filename(directory: &str) -> String {
+    Path::new(directory).join("params.yml").to_string_lossy().to_string()
+  }
+
+  /// Read the settings of an input directory, which has none without a
+  /// params.yml.
+  pub fn read(directory: &str) -> Result<Self, String> {
+    let filename = Self::make_filename(directory);
+    match fs::read_to_string(&filename) {
+      Ok(text) => serde_yaml::from_str(&text).map_err(|e| format!("can't parse {filename}: {e}")),
+      Err(_) => Ok(Self::default()),
+    }
+  }
+
+  /// Set a parameter from text such as "day14.width=11".
+  pub fn set(&mut self, assignment: &str) -> Result<(), String> {
+    let invalid = || format!("invalid parameter '{assignment}', expected day.name=value");
+    let (name, value) = assignment.split_once('=').ok_or_else(invalid)?;
+    let (day, name) = name.trim().split_once('.').ok_or_else(invalid)?;
+    let value = value.trim().parse().map_err(|_| invalid())?;
+    self.days.entry(day.to_string()).or_default().insert(name.to_string(), value);
+    Ok(())
+  }
+
+  /// These settings with the ones in overrides replacing them.
+  pub fn with(&self, overrides: &Settings) -> Settings {
+    let mut days = self.days.clone();
+    for (day, values) in &overrides.days {
+      days.entry(day.clone()).or_default().extend(values.iter().map(|(n, v)| (n.clone(), *v)));
+    }
+    Settings { days }
+  }
+
+  /// Check that every parameter belongs to a day, that the day declares it
+  /// and that its value is in the declared range, given the names of the
+  /// days and their declared parameters.
+  pub fn validate(&self, names: &[&str], declared: &[&[Param]]) -> Result<(), String> {
+    for (day, values) in &self.days {
+      let index = names.iter().position(|n| n == day)
+        .ok_or_else(|| format!("{day} is not implemented"))?;
+      for (name, value) in values {
+        let Some((_, _, range)) = declared[index].iter().find(|(n, _, _)| n == name) else {
+          let known: Vec<&str> = declared[index].iter().map(|(n, _, _)| *n).collect();
+          let known = if known.is_empty() { "none".to_string() } else { known.join(", ") };
+          return Err(format!("{day} has no parameter {name} (it has: {known})"));
+        };
+        if !range.contains(value) {
+          return Err(format!("{day}.{name} must be from {} to {}, not {value}", range.start(),
+                             range.end()));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// The parameters that were set for a day.
+  pub fn for_day(&self, day: &str) -> Params {
+    Params { values: self.days.get(day).cloned().unwrap_or_default() }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Param, Params, Settings};
+
+  const NAMES: &[&str] = &["day1", "day14"];
+  const DECLARED: &[&[Param]] = &[&[], &[("width", 101, 1..=999), ("height", 103, 1..=999)]];
+
+  #[test]
+  fn test_params() {
+    let defaults = Params::from(DECLARED[1]);
+    let mut overrides = Params::default();
+    overrides.set("width", 11);
+    let params = defaults.with(&overrides);
+    assert_eq!(11, params.get("width"));
+    assert_eq!(103, params.get("height"));
+  }
+
+  #[test]
+  fn test_settings() {
+    let mut settings = Settings::default();
+    settings.set("day14.width=11").unwrap();
+    assert_eq!(Ok(()), settings.validate(NAMES, DECLARED));
+    assert_eq!(11, settings.for_day("day14").get("width"));
+    assert_eq!(Params::default(), settings.for_day("day1"));
+
+    let file: Settings = serde_yaml::from_str("day14:\n  width: 5\n  height: 7\n").unwrap();
+    let merged = file.with(&settings);
+    assert_eq!(11, merged.for_day("day14").get("width"));
+    assert_eq!(7, merged.for_day("day14").get("height"));
+  }
+
+  #[test]
+  fn test_invalid() {
+    let mut settings = Settings::default();
+    assert_eq!(Err("invalid parameter 'day14=11', expected day.name=value".to_string()),
+               settings.set("day14=11"));
+    assert!(settings.set("day14.width=x").is_err());
+    settings.set("day14.depth=3").unwrap();
+    assert_eq!(Err("day14 has no parameter depth (it has: width, height)".to_string()),
+               settings.validate(NAMES, DECLARED));
+    let mut settings = Settings::default();
+    settings.set("day1.size=3").unwrap();
+    assert_eq!(Err("day1 has no parameter size (it has: none)".to_string()),
+               settings.validate(NAMES, DECLARED));
+    let mut settings = Settings::default();
+    settings.set("day9.size=3").unwrap();
+    assert_eq!(Err("day9 is not implemented".to_string()),
+               settings.validate(NAMES, DECLARED));
+  }
+
+  #[test]
+  fn test_out_of_range() {
+    for (value, valid) in [(-1, false), (0, false), (1, true), (999, true), (1000, false)] {
+      let mut settings = Settings::default();
+      settings.set(&format!("day14.height={value}")).unwrap();
+      let expected = if valid {
+        Ok(())
+      } else {
+        Err(format!("day14.height must be from 1 to 999, not {value}"))
+      };
+      assert_eq!(expected, settings.validate(NAMES, DECLARED));
+    }
+  }
+}
@@ -0,0 +1,497 @@
+use crate::alloc::AllocStats;
+use crate::answer::Answer;
+use crate::utils::{DayResult, Outcome, Status, Timing};
+
+use serde::Serialize;
+use std::str::FromStr;
+use std::time;
+
+/// The version of the machine-readable report layout. Bump it whenever a
+/// field is renamed, removed or changes type so that consumers can tell the
+/// difference. Version 2 writes numeric answers as numbers.
+pub const REPORT_VERSION: u32 = 2;
+
+/// The columns of the CSV report, where input is the input directory. The
+/// allocation columns are empty unless built with the alloc-stats feature.
+pub const CSV_HEADER: &str = "day,phase,time_ns,answer,samples,min_ns,median_ns,mean_ns,\
+  std_dev_ns,alloc_count,alloc_bytes,peak_bytes,input\n";
+
+/// The ways the results of a run can be written out.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+  #[default]
+  Text,
+  Json,
+  Csv,
+  /// A table with a row per day, to paste into a README
+  Markdown,
+  /// The same table as a standalone page, to publish from CI
+  Html,
+}
+
+impl FromStr for Format {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "text" => Ok(Format::Text),
+      "json" => Ok(Format::Json),
+      "csv" => Ok(Format::Csv),
+      "markdown" | "md" => Ok(Format::Markdown),
+      "html" => Ok(Format::Html),
+      _ => Err(format!("unknown format '{s}', expected one of text, json, csv, markdown or html")),
+    }
+  }
+}
+
+/// The statistics over the timed runs of one phase.
+#[derive(Debug, Serialize)]
+pub struct TimingReport {
+  pub samples: usize,
+  pub min_ns: u64,
+  pub median_ns: u64,
+  pub mean_ns: u64,
+  pub std_dev_ns: u64,
+}
+
+/// The time and answer for one part of a day.
+#[derive(Debug, Serialize)]
+pub struct PartReport {
+  pub part: usize,
+  /// The median time over the timed runs
+  pub time_ns: u64,
+  pub timing: TimingReport,
+  /// A number for a numeric answer, otherwise a string
+  pub answer: Answer,
+  /// The allocations per run, or null without the alloc-stats feature
+  pub alloc: Option<AllocStats>,
+  /// How the answer compares with the known answer: pass, fail or unknown,
+  /// or null when it wasn't checked
+  pub status: Option<String>,
+}
+
+/// The times and answers for one day.
+#[derive(Debug, Serialize)]
+pub struct DayReport {
+  pub day: String,
+  /// The median time over the timed runs
+  pub generator_ns: u64,
+  pub generator_timing: TimingReport,
+  /// The allocations per run, or null without the alloc-stats feature
+  pub generator_alloc: Option<AllocStats>,
+  /// The parts that ran
+  pub parts: Vec<PartReport>,
+  /// How the day ended: ok, no_input, invalid_input, timed_out or panicked
+  pub outcome: String,
+  /// Why the day failed, or null if it succeeded
+  pub error: Option<String>,
+  /// The worst status of the day's parts, or null when they weren't checked
+  pub status: Option<String>,
+}
+
+/// The serializable summary of a whole run.
+#[derive(Debug, Serialize)]
+pub struct Report {
+  pub version: u32,
+  pub input: String,
+  /// The wall-clock time of the run
+  pub overall_ns: u64,
+  /// The sum of each day's own time, which is larger than overall_ns when
+  /// days ran in parallel
+  pub summed_ns: u64,
+  pub days: Vec<DayReport>,
+}
+
+pub(crate) fn nanos(duration: time::Duration) -> u64 {
+  duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+impl From<&Timing> for TimingReport {
+  fn from(timing: &Timing) -> Self {
+    TimingReport {
+      samples: timing.samples.len(),
+      min_ns: nanos(timing.min()),
+      median_ns: nanos(timing.median()),
+      mean_ns: nanos(timing.mean()),
+      std_dev_ns: nanos(timing.std_dev()),
+    }
+  }
+}
+
+impl From<&DayResult> for DayReport {
+  fn from(result: &DayResult) -> Self {
+    let parts = [&result.part1, &result.part2].iter().enumerate()
+      .filter_map(|(i, part)| part.as_ref().map(|p| (i, p)))
+      .map(|(i, (timing, answer))| PartReport {
+        part: i + 1,
+        time_ns: nanos(timing.median()),
+        timing: TimingReport::from(timing),
+        answer: answer.clone(),
+        alloc: result.allocs[i + 1],
+        status: None,
+      })
+      .collect();
+    DayReport {
+      day: result.day.clone(),
+      generator_ns: nanos(result.generate_time.median()),
+      generator_timing: TimingReport::from(&result.generate_time),
+      generator_alloc: result.allocs[0],
+      parts,
+      outcome: result.outcome.name().to_string(),
+      error: match &result.outcome {
+        Outcome::Ok => None,
+        failure => Some(failure.to_string()),
+      },
+      status: None,
+    }
+  }
+}
+
+impl Report {
+  pub fn new(input: &str, overall: time::Duration, summed: time::Duration,
+             results: &[DayResult]) -> Self {
+    Report {
+      version: REPORT_VERSION,
+      input: input.to_string(),
+      overall_ns: nanos(overall),
+      summed_ns: nanos(summed),
+      days: results.iter().map(DayReport::from).collect(),
+    }
+  }
+
+  /// Record how each day's parts compare with the known answers, given
+  /// the statuses from DayResult::check in the order of the days.
+  pub fn with_checks(mut self, checks: &[Vec<Status>]) -> Self {
+    for (day, statuses) in self.days.iter_mut().zip(checks) {
+      for part in &mut day.parts {
+        part.status = statuses.get(part.part - 1).map(|s| s.name().to_string());
+      }
+      day.status = Some(Status::summarize(statuses).name().to_string());
+    }
+    self
+  }
+
+  pub fn to_json(&self) -> String {
+    serde_json::to_string_pretty(self).expect("Can't serialize report")
+  }
+
+  /// Render as CSV with one row per phase of each day. A day that failed
+  /// gets an "error" row with the reason in the answer column. The last rows
+  /// hold the overall and summed runtimes with the day set to "all".
+  pub fn to_csv(&self) -> String {
+    String::from(CSV_HEADER) + &self.csv_rows()
+  }
+
+  /// The CSV rows without the header, so several reports can share one.
+  pub fn csv_rows(&self) -> String {
+    let input = csv_escape(&self.input);
+    let mut out = String::new();
+    for day in &self.days {
+      out += &format!("{},generator,{},,{},{},{}\n", day.day, day.generator_ns,
+                      csv_timing(&day.generator_timing), csv_alloc(&day.generator_alloc), input);
+      for part in &day.parts {
+        out += &format!("{},part{},{},{},{},{},{}\n", day.day, part.part, part.time_ns,
+                        csv_escape(&part.answer.to_string()), csv_timing(&part.timing),
+                        csv_alloc(&part.alloc), input);
+      }
+      if let Some(error) = &day.error {
+        out += &format!("{},error,,{},,,,,,,,,{}\n", day.day, csv_escape(error), input);
+      }
+    }
+    out += &format!("all,overall,{},,,,,,,,,,{}\n", self.overall_ns, input);
+    out += &format!("all,summed,{},,,,,,,,,,{}\n", self.summed_ns, input);
+    out
+  }
+}
+
+/// The width of the longest bar in the Markdown table, in characters.
+const BAR_WIDTH: u64 = 20;
+
+/// The columns of the Markdown and HTML tables.
+const TABLE_HEADER: [&str; 7] =
+  ["Day", "Generator", "Part 1", "Part 2", "Total", "Answers", "Relative time"];
+
+impl DayReport {
+  /// The time spent in the generator and the parts that ran.
+  fn total_ns(&self) -> u64 {
+    self.generator_ns + self.parts.iter().map(|p| p.time_ns).sum::<u64>()
+  }
+
+  fn part_ns(&self, part: usize) -> Option<u64> {
+    self.parts.iter().find(|p| p.part == part).map(|p| p.time_ns)
+  }
+
+  /// The cells of the day's row in the table, without the bar.
+  fn cells(&self) -> [String; 6] {
+    let generator = match self.outcome.as_str() {
+      "no_input" => None,
+      _ => Some(self.generator_ns),
+    };
+    let mut check = match self.status.as_deref() {
+      Some("pass") => "✅ pass".to_string(),
+      Some("fail") => "❌ fail".to_string(),
+      Some("unknown") => "❔ unknown".to_string(),
+      Some(status) => status.replace('_', " "),
+      None => String::new(),
+    };
+    if self.outcome != "ok" && self.outcome != "no_input" {
+      check = format!("{check} ({})", self.outcome.replace('_', " ")).trim_start().to_string();
+    }
+    [self.day.replace("day", "Day "), cell_time(generator), cell_time(self.part_ns(1)),
+     cell_time(self.part_ns(2)), cell_time(generator.map(|_| self.total_ns())), check]
+  }
+}
+
+fn cell_time(ns: Option<u64>) -> String {
+  ns.map_or_else(|| "–".to_string(), |ns| format!("{:.2?}", time::Duration::from_nanos(ns)))
+}
+
+/// A bar of block characters whose length is value relative to longest,
+/// in eighths of a character.
+fn text_bar(value: u64, longest: u64) -> String {
+  if longest == 0 {
+    return String::new();
+  }
+  let eighths = (value as u128 * BAR_WIDTH as u128 * 8 / longest as u128) as usize;
+  let partial = ["", "▏", "▎", "▍", "▌", "▋", "▊", "▉"][eighths % 8];
+  "█".repeat(eighths / 8) + partial
+}
+
+impl Report {
+  /// The cells of the total row: the time of each phase over all days, and
+  /// how many of the checked days passed.
+  fn total_cells(&self) -> [String; 6] {
+    let sum = |f: &dyn Fn(&DayReport) -> Option<u64>| -> u64 { self.days.iter().flat_map(f).sum() };
+    let checked: Vec<&str> = self.days.iter()
+      .filter_map(|d| d.status.as_deref())
+      .filter(|s| ["pass", "fail", "unknown"].contains(s))
+      .collect();
+    let passed = checked.iter().filter(|s| **s == "pass").count();
+    let answers = if checked.is_empty() {
+      String::new()
+    } else {
+      format!("{passed}/{} pass", checked.len())
+    };
+    ["Total".to_string(), cell_time(Some(sum(&|d| Some(d.generator_ns)))),
+     cell_time(Some(sum(&|d| d.part_ns(1)))), cell_time(Some(sum(&|d| d.part_ns(2)))),
+     cell_time(Some(self.summed_ns)), answers]
+  }
+
+  fn longest_ns(&self) -> u64 {
+    self.days.iter().map(DayReport::total_ns).max().unwrap_or(0)
+  }
+
+  /// Render as a Markdown table with a row per day and a bar for its share
+  /// of the slowest day's time, followed by a total row.
+  pub fn to_markdown(&self) -> String {
+    let longest = self.longest_ns();
+    let mut out = format!("### Results for {}\n\n", markdown_escape(&self.input));
+    out += &format!("| {} |\n", TABLE_HEADER.join(" | "));
+    out += "|:----|----------:|-------:|-------:|------:|:--------|:--------------|\n";
+    for day in &self.days {
+      let cells = day.cells().map(|c| markdown_escape(&c));
+      out += &format!("| {} | {} |\n", cells.join(" | "), text_bar(day.total_ns(), longest));
+    }
+    let totals = self.total_cells().map(|c| if c.is_empty() { c } else { format!("**{c}**") });
+    out += &format!("| {} | |\n", totals.join(" | "));
+    out += &format!("\nOverall runtime {} (sum of days {})\n", cell_time(Some(self.overall_ns)),
+                    cell_time(Some(self.summed_ns)));
+    out
+  }
+
+  /// The table as an HTML section, for to_html.
+  fn html_section(&self) -> String {
+    let longest = self.longest_ns();
+    let mut out = format!("<section>\n<h2>Results for {}</h2>\n<table>\n",
+                          html_escape(&self.input));
+    out += &format!("<thead><tr>{}</tr></thead>\n<tbody>\n",
+                    TABLE_HEADER.map(|h| format!("<th>{h}</th>")).concat());
+    for day in &self.days {
+      let [name, times @ .., check] = day.cells();
+      let width = if longest == 0 { 0.0 } else { day.total_ns() as f64 * 100.0 / longest as f64 };
+      out += &format!("<tr><th>{}</th>{}<td class=\"{}\">{}</td>\
+<td><div class=\"bar\" style=\"width: {width:.1}%\"></div></td></tr>\n",
+                      html_escape(&name), times.map(|t| format!("<td>{t}</td>")).concat(),
+                      day.status.as_deref().unwrap_or(""), html_escape(&check));
+    }
+    let [name, times @ .., answers] = self.total_cells();
+    out += &format!("</tbody>\n<tfoot><tr><th>{name}</th>{}<td>{answers}</td><td></td></tr>\
+</tfoot>\n</table>\n", times.map(|t| format!("<td>{t}</td>")).concat());
+    out += &format!("<p>Overall runtime {} (sum of days {})</p>\n</section>\n",
+                    cell_time(Some(self.overall_ns)), cell_time(Some(self.summed_ns)));
+    out
+  }
+}
+
+/// Render the reports as a standalone HTML page with a table for each.
+pub fn to_html(reports: &[&Report]) -> String {
+  let sections: String = reports.iter().map(|r| r.html_section()).collect();
+  format!("<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>Advent of Code 2024 results</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; }}
+th, td {{ padding: 0.2em 0.8em; border-bottom: 1px solid #ddd; text-align: right; }}
+th:first-child, td:nth-child(6), td:last-child {{ text-align: left; }}
+td:last-child {{ width: 12em; }}
+tfoot {{ font-weight: bold; }}
+.pass {{ color: #080; }}
+.fail {{ color: #c00; font-weight: bold; }}
+.unknown, .no_input {{ color: #b80; }}
+.bar {{ height: 0.8em; background: #48c; }}
+</style>
+</head>
+<body>
+{sections}</body>
+</html>
+")
+}
+
+/// Escape the characters that would break a Markdown table cell.
+fn markdown_escape(text: &str) -> String {
+  text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn html_escape(text: &str) -> String {
+  text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn csv_alloc(alloc: &Option<AllocStats>) -> String {
+  match alloc {
+    Some(a) => format!("{},{},{}", a.count, a.bytes, a.peak_bytes),
+    None => String::from(",,"),
+  }
+}
+
+fn csv_timing(timing: &TimingReport) -> String {
+  format!("{},{},{},{},{}", timing.samples, timing.min_ns, timing.median_ns, timing.mean_ns,
+          timing.std_dev_ns)
+}
+
+/// Quote a CSV field if it contains anything that would break the row.
+fn csv_escape(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.to_string()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{to_html, Format, Report};
+  use crate::alloc::AllocStats;
+  use crate::answer::Answer;
+  use crate::day::ParseError;
+  use crate::utils::{DayResult, Outcome, Status, Timing};
+  use std::time::Duration;
+
+  fn results() -> Vec<DayResult> {
+    vec![DayResult {
+      day: "day17".to_string(),
+      outcome: Outcome::Ok,
+      generate_time: Duration::from_nanos(10).into(),
+      part1: Some((Timing {
+        samples: vec![Duration::from_nanos(20), Duration::from_nanos(18), Duration::from_nanos(40)],
+      }, Answer::from("4,6,3"))),
+      part2: Some((Duration::from_nanos(30).into(), Answer::Int(117440))),
+      allocs: [Some(AllocStats { count: 3, bytes: 96, peak_bytes: 64 }), None,
+               Some(AllocStats::default())],
+    }, DayResult {
+      day: "day18".to_string(),
+      outcome: Outcome::InvalidInput(ParseError::invalid("", "no bytes, fell")),
+      generate_time: Duration::from_nanos(5).into(),
+      part1: None,
+      part2: None,
+      allocs: [None; 3],
+    }]
+  }
+
+  #[test]
+  fn test_format() {
+    assert_eq!(Ok(Format::Json), "json".parse());
+    assert_eq!(Ok(Format::Csv), "csv".parse());
+    assert_eq!(Ok(Format::Text), "text".parse());
+    assert_eq!(Ok(Format::Markdown), "md".parse());
+    assert_eq!(Ok(Format::Html), "html".parse());
+    assert!("yaml".parse::<Format>().is_err());
+  }
+
+  #[test]
+  fn test_json() {
+    let report = Report::new("input", Duration::from_nanos(100), Duration::from_nanos(60),
+                             &results());
+    let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+    assert_eq!(json["version"], 2);
+    assert_eq!(json["overall_ns"], 100);
+    assert_eq!(json["summed_ns"], 60);
+    assert_eq!(json["days"][0]["day"], "day17");
+    assert_eq!(json["days"][0]["generator_ns"], 10);
+    assert_eq!(json["days"][0]["parts"][1]["part"], 2);
+    assert_eq!(json["days"][0]["parts"][1]["time_ns"], 30);
+    assert_eq!(json["days"][0]["parts"][0]["answer"], "4,6,3");
+    assert_eq!(json["days"][0]["parts"][1]["answer"], 117440);
+    assert_eq!(json["days"][0]["parts"][0]["time_ns"], 20);
+    assert_eq!(json["days"][0]["parts"][0]["timing"]["samples"], 3);
+    assert_eq!(json["days"][0]["parts"][0]["timing"]["min_ns"], 18);
+    assert_eq!(json["days"][0]["parts"][0]["timing"]["mean_ns"], 26);
+    assert_eq!(json["days"][0]["generator_timing"]["std_dev_ns"], 0);
+    assert_eq!(json["days"][0]["generator_alloc"]["peak_bytes"], 64);
+    assert!(json["days"][0]["parts"][0]["alloc"].is_null());
+    assert_eq!(json["days"][0]["parts"][1]["alloc"]["count"], 0);
+    assert!(json["days"][0]["error"].is_null());
+    assert_eq!(json["days"][0]["outcome"], "ok");
+    assert_eq!(json["days"][1]["outcome"], "invalid_input");
+    assert_eq!(json["days"][1]["error"], "invalid input: no bytes, fell");
+    assert_eq!(json["days"][1]["parts"].as_array().unwrap().len(), 0);
+  }
+
+  #[test]
+  fn test_csv() {
+    let report = Report::new("input", Duration::from_nanos(100), Duration::from_nanos(60),
+                             &results());
+    assert_eq!(report.to_csv(), "day,phase,time_ns,answer,samples,min_ns,median_ns,mean_ns,\
+std_dev_ns,alloc_count,alloc_bytes,peak_bytes,input
+day17,generator,10,,1,10,10,10,0,3,96,64,input
+day17,part1,20,\"4,6,3\",3,18,20,26,12,,,,input
+day17,part2,30,117440,1,30,30,30,0,0,0,0,input
+day18,generator,5,,1,5,5,5,0,,,,input
+day18,error,,\"invalid input: no bytes, fell\",,,,,,,,,input
+all,overall,100,,,,,,,,,,input
+all,summed,60,,,,,,,,,,input
+");
+  }
+
+  #[test]
+  fn test_markdown() {
+    let report = Report::new("input", Duration::from_nanos(100), Duration::from_nanos(60),
+                             &results())
+      .with_checks(&[vec![Status::Pass, Status::Unknown], vec![Status::Fail, Status::Fail]]);
+    assert_eq!(Some("unknown"), report.days[0].parts[1].status.as_deref());
+    assert_eq!(report.to_markdown(), "### Results for input
+
+| Day | Generator | Part 1 | Part 2 | Total | Answers | Relative time |
+|:----|----------:|-------:|-------:|------:|:--------|:--------------|
+| Day 17 | 10.00ns | 20.00ns | 30.00ns | 60.00ns | ❔ unknown | ████████████████████ |
+| Day 18 | 5.00ns | – | – | 5.00ns | ❌ fail (invalid input) | █▋ |
+| **Total** | **15.00ns** | **20.00ns** | **30.00ns** | **60.00ns** | **0/2 pass** | |
+
+Overall runtime 100.00ns (sum of days 60.00ns)
+");
+  }
+
+  #[test]
+  fn test_html() {
+    let report = Report::new("<in>", Duration::from_nanos(100), Duration::from_nanos(60),
+                             &results());
+    let html = to_html(&[&report]);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h2>Results for &lt;in&gt;</h2>"));
+    assert!(html.contains("<tr><th>Day 18</th><td>5.00ns</td><td>–</td><td>–</td><td>5.00ns</td>\
+<td class=\"\">(invalid input)</td><td><div class=\"bar\" style=\"width: 8.3%\"></div></td></tr>"));
+    assert!(html.trim_end().ends_with("</html>"));
+  }
+}
@@ -0,0 +1,155 @@
+use std::fs;
+use std::path::Path;
+
+/// The source of a new day's module, with placeholder parts and a test
+/// skeleton for the example from the puzzle.
+pub fn module_template(day: u32) -> String {
+  format!(r#"use crate::day::{{Day, Example, ParseError}};
+
+// The example from https://adventofcode.com/2024/day/{day}
+const EXAMPLE: &str = "";
+
+pub struct Solution;
+
+impl Day for Solution {{
+    type Input = Vec<String>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {{ input: EXAMPLE, answers: [None, None], params: &[] }},
+    ];
+
+    fn generator(input: &str) -> Result<Self::Input, ParseError> {{
+        generator(input)
+    }}
+
+    fn part1(input: &Self::Input) -> usize {{
+        part1(input)
+    }}
+
+    fn part2(input: &Self::Input) -> usize {{
+        part2(input)
+    }}
+}}
+
+pub fn generator(input: &str) -> Result<Vec<String>, ParseError> {{
+    Ok(input.lines().map(|l| l.to_string()).collect())
+}}
+
+pub fn part1(_input: &[String]) -> usize {{
+    0
+}}
+
+pub fn part2(_input: &[String]) -> usize {{
+    0
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::{{generator, part1, part2, EXAMPLE}};
+
+    #[test]
+    fn test_generator() {{
+        let input = generator(EXAMPLE).unwrap();
+        assert_eq!(input.len(), 0);
+    }}
+
+    #[test]
+    fn test_part_1() {{
+        let input = generator(EXAMPLE).unwrap();
+        assert_eq!(part1(&input), 0);
+    }}
+
+    #[test]
+    fn test_part_2() {{
+        let input = generator(EXAMPLE).unwrap();
+        assert_eq!(part2(&input), 0);
+    }}
+}}
+"#)
+}
+
+/// Add a day to the `name!(1, 2, ...);` invocation in source, keeping the
+/// days in order. Fails if the invocation is missing or already has the day.
+pub fn register(source: &str, name: &str, day: u32) -> Result<String, String> {
+  let open = format!("{name}!(");
+  let start = source.find(&open).ok_or_else(|| format!("can't find {name}!"))? + open.len();
+  let end = start + source[start..].find(')').ok_or_else(|| format!("{name}! isn't closed"))?;
+  let mut days = source[start..end].split(',')
+    .map(|d| d.trim())
+    .filter(|d| !d.is_empty())
+    .map(|d| d.parse::<u32>().map_err(|_| format!("{name}! has an invalid day '{d}'")))
+    .collect::<Result<Vec<_>, _>>()?;
+  if days.contains(&day) {
+    return Err(format!("day {day} is already in {name}!"));
+  }
+  days.push(day);
+  days.sort();
+  let list = days.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(", ");
+  Ok(format!("{}{}{}", &source[..start], list, &source[end..]))
+}
+
+/// Create src/dayN.rs, register it in the day list and the benchmarks, and
+/// create an empty input/dayN.txt if there is none. Nothing is written if
+/// the day already exists. Returns the files that were written.
+pub fn new_day(root: &Path, day: u32) -> Result<Vec<String>, String> {
+  if !(1..=25).contains(&day) {
+    return Err(format!("there is no day {day} in Advent of Code"));
+  }
+  let module = root.join(format!("src/day{day}.rs"));
+  let lib = root.join("src/lib.rs");
+  let bench = root.join("benches/bench.rs");
+  let input = root.join(format!("input/day{day}.txt"));
+  if module.exists() {
+    return Err(format!("{} already exists", module.display()));
+  }
+  let read = |path: &Path| fs::read_to_string(path)
+    .map_err(|e| format!("can't read {}: {e}", path.display()));
+  // Check everything before writing anything, so a failure changes nothing
+  let lib_source = register(&read(&lib)?, "day_list", day)?;
+  let bench_source = register(&read(&bench)?, "benchmarks", day)?;
+
+  let mut written = Vec::new();
+  let mut write = |path: &Path, contents: &str| {
+    fs::write(path, contents).map_err(|e| format!("can't write {}: {e}", path.display()))?;
+    written.push(path.display().to_string());
+    Ok::<(), String>(())
+  };
+  write(&module, &module_template(day))?;
+  write(&lib, &lib_source)?;
+  write(&bench, &bench_source)?;
+  if !input.exists() {
+    fs::create_dir_all(root.join("input"))
+      .map_err(|e| format!("can't create the input directory: {e}"))?;
+    write(&input, "")?;
+  }
+  Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{module_template, register};
+
+  #[test]
+  fn test_register() {
+    assert_eq!(Ok("pub mod utils;\n\nday_list!(1, 2, 3, 10);\n".to_string()),
+               register("pub mod utils;\n\nday_list!(1, 2, 10);\n", "day_list", 3));
+    assert_eq!(Ok("benchmarks!(24);".to_string()), register("benchmarks!();", "benchmarks", 24));
+    assert_eq!(Err("day 2 is already in day_list!".to_string()),
+               register("day_list!(1, 2, 10);", "day_list", 2));
+    assert_eq!(Err("can't find benchmarks!".to_string()),
+               register("day_list!(1);", "benchmarks", 2));
+  }
+
+  #[test]
+  fn test_template() {
+    let source = module_template(24);
+    assert!(source.contains("https://adventofcode.com/2024/day/24"));
+    assert!(source.contains("impl Day for Solution {\n"));
+    assert!(source.contains("\nconst EXAMPLE: &str = \"\";\n"));
+    assert!(source.contains("Example { input: EXAMPLE, answers: [None, None], params: &[] },"));
+    assert!(source.contains("let input = generator(EXAMPLE).unwrap();"));
+    assert!(!source.contains("INPUT"));
+  }
+}
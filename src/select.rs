@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+/// A day to run and which of its parts to run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Selection {
+  /// The index of the day in NAMES and FUNCS
+  pub index: usize,
+  pub parts: [bool; 2],
+}
+
+impl Selection {
+  pub fn all(index: usize) -> Self {
+    Selection { index, parts: [true, true] }
+  }
+}
+
+fn parse_number(text: &str, what: &str) -> Result<usize, String> {
+  text.trim().parse().map_err(|_| format!("invalid {what} '{text}'"))
+}
+
+/// Parse day selectors such as "3", "3-7", "1,5,9" or "12:2" (day 12 part 2
+/// only) into the days to run, in day order. Each argument may hold several
+/// comma-separated selectors, and a day that is selected more than once runs
+/// every part that was asked for. An empty list selects every day.
+pub fn parse_selectors(selectors: &[String], names: &[&str]) -> Result<Vec<Selection>, String> {
+  if selectors.is_empty() {
+    return Ok((0..names.len()).map(Selection::all).collect());
+  }
+  let mut chosen: BTreeMap<usize, [bool; 2]> = BTreeMap::new();
+  for selector in selectors.iter().flat_map(|s| s.split(',')) {
+    let (days, part) = match selector.split_once(':') {
+      Some((days, part)) => (days, Some(parse_number(part, "part")?)),
+      None => (selector, None),
+    };
+    let parts = match part {
+      None => [true, true],
+      Some(1) => [true, false],
+      Some(2) => [false, true],
+      Some(p) => return Err(format!("there is no part {p}")),
+    };
+    let (first, last) = match days.split_once('-') {
+      Some((first, last)) => (parse_number(first, "day")?, parse_number(last, "day")?),
+      None => {
+        let day = parse_number(days, "day")?;
+        (day, day)
+      }
+    };
+    if first > last {
+      return Err(format!("empty day range '{days}'"));
+    }
+    for day in first..=last {
+      let index = names.iter().position(|n| *n == format!("day{day}"))
+        .ok_or_else(|| format!("day {day} is not implemented"))?;
+      let entry = chosen.entry(index).or_insert([false, false]);
+      entry[0] |= parts[0];
+      entry[1] |= parts[1];
+    }
+  }
+  Ok(chosen.into_iter().map(|(index, parts)| Selection { index, parts }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{parse_selectors, Selection};
+
+  const NAMES: &[&str] = &["day1", "day2", "day3", "day5", "day12"];
+
+  fn select(selectors: &[&str]) -> Result<Vec<Selection>, String> {
+    let selectors: Vec<String> = selectors.iter().map(|s| s.to_string()).collect();
+    parse_selectors(&selectors, NAMES)
+  }
+
+  #[test]
+  fn test_all() {
+    assert_eq!(Ok((0..5).map(Selection::all).collect()), select(&[]));
+  }
+
+  #[test]
+  fn test_selectors() {
+    assert_eq!(Ok(vec![Selection::all(2)]), select(&["3"]));
+    assert_eq!(Ok(vec![Selection::all(1), Selection::all(2)]), select(&["2-3"]));
+    assert_eq!(Ok(vec![Selection::all(0), Selection::all(3)]), select(&["5,1"]));
+    assert_eq!(Ok(vec![Selection::all(0), Selection::all(3)]), select(&["5", "1"]));
+    assert_eq!(Ok(vec![Selection { index: 4, parts: [false, true] }]), select(&["12:2"]));
+    assert_eq!(Ok(vec![Selection { index: 0, parts: [true, false] },
+                       Selection { index: 1, parts: [true, false] }]), select(&["1-2:1"]));
+    assert_eq!(Ok(vec![Selection::all(4)]), select(&["12:1,12:2"]));
+  }
+
+  #[test]
+  fn test_invalid() {
+    assert_eq!(Err("day 4 is not implemented".to_string()), select(&["3-5"]));
+    assert_eq!(Err("there is no part 3".to_string()), select(&["1:3"]));
+    assert_eq!(Err("invalid day 'x'".to_string()), select(&["x"]));
+    assert_eq!(Err("empty day range '3-1'".to_string()), select(&["3-1"]));
+  }
+}
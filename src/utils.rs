@@ -1,17 +1,32 @@
+pub mod grid;
+pub mod search;
+
+use crate::alloc::{self, AllocStats};
+use crate::answer::Answer;
+use crate::day::{Day, Example, ParseError};
+use crate::params::Params;
+
 use colored::Colorize;
+use std::any::Any;
 use std::cmp::min;
 use std::{fmt, fs, io};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
-use std::time;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::{thread, time};
 
 /// Format the output of each line of the output.
 /// Includes the category, time, and result.
 fn pretty_print(f: &mut fmt::Formatter<'_>, line: &str,
-                duration: time::Duration,
+                duration: &Timing, allocs: Option<&AllocStats>,
                 output: Option<&str>) -> fmt::Result {
   const DISPLAY_WIDTH: usize = 40;
 
-  let duration = format!("({:.2?})", duration);
+  let duration = match allocs {
+    Some(allocs) => format!("{duration} {allocs}"),
+    None => duration.to_string(),
+  };
   write!(f, "{} {}", line, duration.dimmed())?;
 
   match output {
@@ -43,44 +58,450 @@ pub fn time<T>(func: &dyn Fn() -> T) -> (time::Duration, T) {
   (start.elapsed(), result)
 }
 
+/// How each day's code should be run.
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+  /// The number of timed runs of each phase
+  pub repeat: usize,
+  /// The number of untimed runs of each phase before the timed ones
+  pub warmup: usize,
+  /// Which of the two parts to run
+  pub parts: [bool; 2],
+  /// How long each phase, with all of its runs, may take
+  pub timeout: Option<time::Duration>,
+  /// The parameters of the day that replace its defaults
+  pub params: Params,
+}
+
+impl Default for RunOptions {
+  fn default() -> Self {
+    RunOptions { repeat: 1, warmup: 0, parts: [true, true], timeout: None,
+                 params: Params::default() }
+  }
+}
+
+/// The durations of repeated runs of the same code.
+#[derive(Clone, Debug, Default)]
+pub struct Timing {
+  pub samples: Vec<time::Duration>,
+}
+
+impl Timing {
+  pub fn min(&self) -> time::Duration {
+    self.samples.iter().min().copied().unwrap_or_default()
+  }
+
+  pub fn median(&self) -> time::Duration {
+    let mut sorted = self.samples.clone();
+    sorted.sort();
+    match sorted.len() {
+      0 => time::Duration::ZERO,
+      n if n % 2 == 1 => sorted[n / 2],
+      n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+    }
+  }
+
+  pub fn mean(&self) -> time::Duration {
+    if self.samples.is_empty() {
+      return time::Duration::ZERO;
+    }
+    self.samples.iter().sum::<time::Duration>() / self.samples.len() as u32
+  }
+
+  /// The sample standard deviation, which is zero with fewer than two samples
+  pub fn std_dev(&self) -> time::Duration {
+    if self.samples.len() < 2 {
+      return time::Duration::ZERO;
+    }
+    let mean = self.mean().as_secs_f64();
+    let variance = self.samples.iter()
+      .map(|s| (s.as_secs_f64() - mean).powi(2))
+      .sum::<f64>() / (self.samples.len() - 1) as f64;
+    time::Duration::from_secs_f64(variance.sqrt())
+  }
+}
+
+impl From<time::Duration> for Timing {
+  fn from(duration: time::Duration) -> Self {
+    Timing { samples: vec![duration] }
+  }
+}
+
+impl fmt::Display for Timing {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if self.samples.len() > 1 {
+      write!(f, "(median {:.2?} min {:.2?} mean {:.2?} ± {:.2?})", self.median(), self.min(),
+             self.mean(), self.std_dev())
+    } else {
+      write!(f, "({:.2?})", self.median())
+    }
+  }
+}
+
+/// Time func over the warmup and timed runs given by the options, returning
+/// the result of the last run. Every result is compared to the first one with
+/// `same` and a difference is treated as a bug in the day.
+pub fn time_repeated<T>(options: &RunOptions, func: &dyn Fn() -> T,
+                        same: fn(&T, &T) -> bool) -> (Timing, T) {
+  let mut first: Option<T> = None;
+  let mut last: Option<T> = None;
+  let mut samples = Vec::with_capacity(options.repeat);
+  for run in 0..options.warmup + options.repeat.max(1) {
+    let (elapsed, result) = time(func);
+    if run >= options.warmup {
+      samples.push(elapsed);
+    }
+    match &first {
+      None => first = Some(result),
+      Some(f) => {
+        if !same(f, &result) {
+          panic!("Run {} gave a different result than the first run", run + 1);
+        }
+        last = Some(result);
+      }
+    }
+  }
+  (Timing { samples }, last.or(first).unwrap())
+}
+
+/// Apply func to each item using up to `jobs` threads. The results are
+/// returned in the same order as the items.
+pub fn parallel_map<I, T, F>(jobs: usize, items: &[I], func: F) -> Vec<T>
+  where I: Sync, T: Send, F: Fn(&I) -> T + Sync {
+  let jobs = jobs.clamp(1, items.len().max(1));
+  if jobs == 1 {
+    return items.iter().map(func).collect();
+  }
+  let next = AtomicUsize::new(0);
+  let mut results: Vec<(usize, T)> = thread::scope(|scope| {
+    let workers: Vec<_> = (0..jobs).map(|_| scope.spawn(|| {
+      let mut done = Vec::new();
+      loop {
+        let idx = next.fetch_add(1, Ordering::Relaxed);
+        if idx >= items.len() {
+          break done;
+        }
+        done.push((idx, func(&items[idx])));
+      }
+    })).collect();
+    workers.into_iter().flat_map(|w| w.join().unwrap()).collect()
+  });
+  results.sort_by_key(|(idx, _)| *idx);
+  results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// The path of a day's input file in the in_dir.
+pub fn input_path(in_dir: &str, day: &str) -> String {
+  format!("{in_dir}/{day}.txt")
+}
+
 /// Read the data files from the in_dir into a vector of string.
 pub fn read_inputs(in_dir: &str, days: &[&str]) -> io::Result<Vec<String>> {
   let data: Vec<io::Result<String>> = days.iter()
     .map(|&day| {
-      let filename = format!("{in_dir}/{day}.txt");
-      fs::read_to_string(Path::new(&filename))
+      fs::read_to_string(Path::new(&input_path(in_dir, day)))
     })
     .collect();
   data.into_iter().collect()
 }
 
-/// The times and results of running a day's code.
+/// How running a day's code ended.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+  Ok,
+  /// The input file couldn't be read, so nothing ran
+  NoInput { path: String, reason: String },
+  /// The generator rejected the input, so neither part ran
+  InvalidInput(ParseError),
+  /// A phase ran for longer than the timeout, so the phases after it didn't run
+  TimedOut { phase: &'static str, limit: time::Duration },
+  /// A phase panicked, so the phases after it didn't run
+  Panicked { phase: &'static str, message: String },
+}
+
+impl Outcome {
+  /// A short, stable name for the machine-readable reports
+  pub fn name(&self) -> &'static str {
+    match self {
+      Outcome::Ok => "ok",
+      Outcome::NoInput { .. } => "no_input",
+      Outcome::InvalidInput(_) => "invalid_input",
+      Outcome::TimedOut { .. } => "timed_out",
+      Outcome::Panicked { .. } => "panicked",
+    }
+  }
+}
+
+impl fmt::Display for Outcome {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Outcome::Ok => write!(f, "ok"),
+      Outcome::NoInput { path, reason } => write!(f, "no input: can't read {path}: {reason}"),
+      Outcome::InvalidInput(e) => write!(f, "invalid input: {e}"),
+      Outcome::TimedOut { phase, limit } => write!(f, "timed out in {phase} after {limit:.2?}"),
+      Outcome::Panicked { phase, message } => write!(f, "panicked in {phase}: {message}"),
+    }
+  }
+}
+
+/// The times and results of running a day's code. A part is None when it
+/// didn't run, either because it wasn't selected or because the day failed.
 pub struct DayResult {
   pub day: String,
-  pub generate_time: time::Duration,
-  pub part1: (time::Duration, String),
-  pub part2: (time::Duration, String),
+  pub outcome: Outcome,
+  pub generate_time: Timing,
+  pub part1: Option<(Timing, Answer)>,
+  pub part2: Option<(Timing, Answer)>,
+  /// The allocations of the generator, part 1 and part 2, which are only
+  /// counted when built with the alloc-stats feature
+  pub allocs: [Option<AllocStats>; 3],
 }
 
 impl DayResult {
+  /// The result for a day whose input couldn't be read
+  pub fn without_input(day: &str, path: &str, reason: &str) -> Self {
+    DayResult {
+      day: day.to_string(),
+      outcome: Outcome::NoInput { path: path.to_string(), reason: reason.to_string() },
+      generate_time: Timing::default(),
+      part1: None,
+      part2: None,
+      allocs: [None; 3],
+    }
+  }
+
   /// Return the pretty name for the day
   pub fn pretty_day(&self) -> String {
     self.day.replace("day", "Day ")
   }
 
-  /// Get the answers without the times
-  pub fn get_answers(&self) -> Vec<String> {
-    vec![self.part1.1.to_string(), self.part2.1.to_string()]
+  /// The median time spent in the generator and the parts that ran
+  pub fn total_time(&self) -> time::Duration {
+    [&self.part1, &self.part2].iter()
+      .flat_map(|p| p.as_ref().map(|(t, _)| t.median()))
+      .sum::<time::Duration>() + self.generate_time.median()
+  }
+
+  /// Get the answers without the times, with None for the parts that didn't run
+  pub fn get_answers(&self) -> Vec<Option<Answer>> {
+    [&self.part1, &self.part2].iter()
+      .map(|p| p.as_ref().map(|(_, a)| a.clone()))
+      .collect()
+  }
+
+  /// Compare each part against the expected answers, if there are any.
+  pub fn check(&self, expected: Option<&Vec<Option<Answer>>>) -> Vec<Status> {
+    [&self.part1, &self.part2].iter().enumerate()
+      .map(|(i, part)| match (part, expected.and_then(|e| e.get(i)).and_then(|e| e.as_ref())) {
+        (None, _) if self.outcome == Outcome::Ok => Status::Skipped,
+        (None, _) if matches!(self.outcome, Outcome::NoInput { .. }) => Status::NoInput,
+        (None, _) => Status::Fail,
+        (Some((_, actual)), Some(e)) if e == actual => Status::Pass,
+        (Some(_), Some(_)) => Status::Fail,
+        (Some(_), None) => Status::Unknown,
+      })
+      .collect()
+  }
+}
+
+/// What a day's worker thread reports as each phase finishes.
+enum Progress {
+  Generated(Timing, Option<AllocStats>, Option<ParseError>),
+  Part(usize, Timing, Option<AllocStats>, Answer),
+  Panicked(String),
+}
+
+fn run_phases<D: Day>(data: &str, options: &RunOptions, progress: &mpsc::Sender<Progress>) {
+  let runs = options.warmup + options.repeat.max(1);
+  let params = D::params(&options.params);
+  // The runner stops listening after a timeout, so failed sends are ignored.
+  // Inputs needn't be comparable, so only the answers of the parts are checked
+  let ((timing, input), allocs) = alloc::measure(runs, || {
+    time_repeated(options, &|| D::parse_with(data, &params), |_, _| true)
+  });
+  let input = match input {
+    Ok(input) => input,
+    Err(e) => {
+      let _ = progress.send(Progress::Generated(timing, allocs, Some(e)));
+      return;
+    }
+  };
+  let _ = progress.send(Progress::Generated(timing, allocs, None));
+  if options.parts[0] {
+    let ((timing, answer), allocs) = alloc::measure(runs, || {
+      time_repeated(options, &|| D::part1_with(&input, &params), PartialEq::eq)
+    });
+    let _ = progress.send(Progress::Part(0, timing, allocs, answer.into()));
+  }
+  if options.parts[1] {
+    let ((timing, answer), allocs) = alloc::measure(runs, || {
+      time_repeated(options, &|| D::part2_with(&input, &params), PartialEq::eq)
+    });
+    let _ = progress.send(Progress::Part(1, timing, allocs, answer.into()));
+  }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+  payload.downcast_ref::<&str>().map(|s| s.to_string())
+    .or_else(|| payload.downcast_ref::<String>().cloned())
+    .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Run a day's generator and both parts, timing each of them. The day runs
+/// on its own thread, so that a panic or a phase that outlives the timeout
+/// ends only that day. A timed out thread can't be stopped, so it is left
+/// running in the background.
+pub fn run_day<D: Day + 'static>(day: &str, data: &str, options: &RunOptions) -> DayResult {
+  let (sender, receiver) = mpsc::channel();
+  let data = data.to_string();
+  let worker_options = options.clone();
+  thread::Builder::new().name(day.to_string()).spawn(move || {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+      run_phases::<D>(&data, &worker_options, &sender)
+    }));
+    if let Err(payload) = result {
+      let _ = sender.send(Progress::Panicked(panic_message(payload.as_ref())));
+    }
+  }).expect("Can't start a thread");
+
+  let mut result = DayResult { day: day.to_string(), outcome: Outcome::Ok,
+                               generate_time: Timing::default(), part1: None, part2: None,
+                               allocs: [None; 3] };
+  let phases = ["generator", "part 1", "part 2"].into_iter().enumerate()
+    .filter(|(i, _)| *i == 0 || options.parts[i - 1])
+    .map(|(_, phase)| phase);
+  for phase in phases {
+    let progress = match options.timeout {
+      Some(limit) => receiver.recv_timeout(limit),
+      None => receiver.recv().map_err(mpsc::RecvTimeoutError::from),
+    };
+    match progress {
+      Ok(Progress::Generated(timing, allocs, error)) => {
+        result.generate_time = timing;
+        result.allocs[0] = allocs;
+        if let Some(e) = error {
+          result.outcome = Outcome::InvalidInput(e);
+          break;
+        }
+      }
+      Ok(Progress::Part(part, timing, allocs, answer)) => {
+        result.allocs[part + 1] = allocs;
+        if part == 0 {
+          result.part1 = Some((timing, answer));
+        } else {
+          result.part2 = Some((timing, answer));
+        }
+      }
+      Ok(Progress::Panicked(message)) => {
+        result.outcome = Outcome::Panicked { phase, message };
+        break;
+      }
+      Err(mpsc::RecvTimeoutError::Timeout) => {
+        result.outcome = Outcome::TimedOut { phase, limit: options.timeout.unwrap() };
+        break;
+      }
+      Err(mpsc::RecvTimeoutError::Disconnected) => {
+        result.outcome = Outcome::Panicked { phase, message: "the thread stopped".to_string() };
+        break;
+      }
+    }
+  }
+  result
+}
+
+/// Run a day on one of its examples with the example's parameters, under
+/// those in the options. Only the selected parts that the example has an
+/// answer for are run.
+pub fn run_example(func: &dyn Fn(&str, &RunOptions) -> DayResult, example: &Example,
+                   options: &RunOptions) -> DayResult {
+  let options = RunOptions {
+    parts: [options.parts[0] && example.answers[0].is_some(),
+            options.parts[1] && example.answers[1].is_some()],
+    params: Params::from(example.params).with(&options.params),
+    ..options.clone()
+  };
+  func(example.input, &options)
+}
+
+/// How a part's answer compares with the known-good answer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+  Pass,
+  Fail,
+  Unknown,
+  Skipped,
+  NoInput,
+}
+
+impl Status {
+  /// The name of the status in machine-readable reports.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Status::Pass => "pass",
+      Status::Fail => "fail",
+      Status::Unknown => "unknown",
+      Status::Skipped => "skipped",
+      Status::NoInput => "no_input",
+    }
+  }
+
+  /// Combine the statuses of a day's parts into one, where the worst wins.
+  pub fn summarize(statuses: &[Status]) -> Status {
+    [Status::Fail, Status::NoInput, Status::Unknown, Status::Pass].into_iter()
+      .find(|s| statuses.contains(s))
+      .unwrap_or(Status::Skipped)
+  }
+}
+
+impl fmt::Display for Status {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    // Pad before coloring so the escape codes don't upset the alignment
+    let width = f.width().unwrap_or(0);
+    match self {
+      Status::Pass => write!(f, "{}", format!("{:width$}", "pass").green()),
+      Status::Fail => write!(f, "{}", format!("{:width$}", "FAIL").red().bold()),
+      Status::Unknown => write!(f, "{}", format!("{:width$}", "unknown").yellow()),
+      Status::Skipped => write!(f, "{}", format!("{:width$}", "skipped").dimmed()),
+      Status::NoInput => write!(f, "{}", format!("{:width$}", "no input").yellow()),
+    }
   }
 }
 
 impl fmt::Display for DayResult {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-    let duration = format!("({:.2?})", self.generate_time + self.part1.0 + self.part2.0);
+    if let Outcome::NoInput { path, reason } = &self.outcome {
+      writeln!(f, "{}", self.pretty_day().bold())?;
+      return writeln!(f, " · {} can't read {}: {}", "No input".yellow().bold(), path, reason);
+    }
+    let duration = format!("({:.2?})", self.total_time());
     writeln!(f, "{} {}", self.pretty_day().bold(), duration.dimmed())?;
-    pretty_print(f," · Generator", self.generate_time, None)?;
-    pretty_print(f, " · Part 1", self.part1.0, Some(&self.part1.1))?;
-    pretty_print(f, " · Part 2", self.part2.0, Some(&self.part2.1))
+    match &self.outcome {
+      Outcome::NoInput { .. } => unreachable!(),
+      Outcome::InvalidInput(e) => {
+        pretty_print(f, " · Generator", &self.generate_time, self.allocs[0].as_ref(), None)?;
+        writeln!(f, " · {} {}", "Invalid input".red().bold(), e)?;
+      }
+      Outcome::TimedOut { phase: "generator", .. }
+        | Outcome::Panicked { phase: "generator", .. } => {}
+      _ => pretty_print(f, " · Generator", &self.generate_time, self.allocs[0].as_ref(), None)?,
+    }
+    for (i, part) in [&self.part1, &self.part2].iter().enumerate() {
+      match part {
+        Some((timing, answer)) =>
+          pretty_print(f, &format!(" · Part {}", i + 1), timing, self.allocs[i + 1].as_ref(),
+                       Some(&answer.to_string()))?,
+        None if self.outcome == Outcome::Ok =>
+          writeln!(f, " · Part {} {}", i + 1, "skipped".dimmed())?,
+        None => {}
+      }
+    }
+    match &self.outcome {
+      Outcome::TimedOut { phase, limit } =>
+        writeln!(f, " · {} in {} after {:.2?}", "Timed out".red().bold(), phase, limit),
+      Outcome::Panicked { phase, message } =>
+        writeln!(f, " · {} in {}: {}", "Panicked".red().bold(), phase, message),
+      _ => Ok(()),
+    }
   }
 }
 
@@ -91,19 +512,22 @@ macro_rules! day_list_internal {
         $(pub mod $day;)*
 
         /// Build a lambda to run each day's code
-        pub const FUNCS : &[&dyn Fn(&str) -> $crate::utils::DayResult] = &[
-            $(&|data| {
-                let (generate_time, input) = $crate::utils::time(&|| $day::generator(data));
-                let part1 = $crate::utils::time(&|| $day::part1(&input));
-                let part2 = $crate::utils::time(&|| $day::part2(&input));
-                $crate::utils::DayResult{day: stringify!($day).to_string(),
-                          generate_time,
-                          part1: (part1.0, part1.1.to_string()),
-                          part2: (part2.0, part2.1.to_string())}},)*
+        pub const FUNCS : &[&(dyn Fn(&str, &$crate::utils::RunOptions) -> $crate::utils::DayResult
+                              + Sync)] = &[
+            $(&|data, options| $crate::utils::run_day::<$day::Solution>(stringify!($day),
+                                                                      data, options),)*
         ];
 
         /// Define the list of implemented day names.
         pub const NAMES: &[&str] = &[$(stringify!($day)),*];
+
+        /// The parameters that each day declares, with their defaults and ranges.
+        pub const PARAMS: &[&[$crate::params::Param]] =
+            &[$(<$day::Solution as $crate::day::Day>::PARAMS),*];
+
+        /// The examples from each day's puzzle text.
+        pub const EXAMPLES: &[&[$crate::day::Example]] =
+            &[$(<$day::Solution as $crate::day::Day>::EXAMPLES),*];
     }
 }
 
@@ -115,4 +539,134 @@ macro_rules! day_list {
 }
 
 pub use day_list_internal;
-pub use day_list;
\ No newline at end of file
+pub use day_list;
+
+#[cfg(test)]
+mod tests {
+  use super::{parallel_map, run_day, run_example, time_repeated, Outcome, RunOptions, Status,
+              Timing};
+  use crate::answer::Answer;
+  use crate::day::{Day, ParseError};
+  use std::cell::Cell;
+  use std::time::Duration;
+
+  /// A day whose first part panics and whose second part never finishes
+  struct Broken;
+
+  impl Day for Broken {
+    type Input = u32;
+    type Output1 = u32;
+    type Output2 = u32;
+
+    fn generator(input: &str) -> Result<u32, ParseError> {
+      crate::day::parse(input)
+    }
+
+    fn part1(input: &u32) -> u32 {
+      assert!(*input != 0, "no zeros");
+      *input
+    }
+
+    fn part2(input: &u32) -> u32 {
+      loop {
+        std::thread::sleep(Duration::from_millis(10));
+        if *input == 0 {
+          return 0;
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn test_timing() {
+    let timing = Timing {
+      samples: [4, 1, 3, 2].iter().map(|&ms| Duration::from_millis(ms)).collect(),
+    };
+    assert_eq!(Duration::from_millis(1), timing.min());
+    assert_eq!(Duration::from_micros(2500), timing.median());
+    assert_eq!(Duration::from_micros(2500), timing.mean());
+    assert_eq!(1290, timing.std_dev().as_micros());
+
+    let timing = Timing::from(Duration::from_millis(7));
+    assert_eq!(Duration::from_millis(7), timing.median());
+    assert_eq!(Duration::ZERO, timing.std_dev());
+  }
+
+  #[test]
+  fn test_time_repeated() {
+    let calls = Cell::new(0);
+    let options = RunOptions { repeat: 5, warmup: 2, ..RunOptions::default() };
+    let (timing, result) = time_repeated(&options, &|| {
+      calls.set(calls.get() + 1);
+      42
+    }, PartialEq::eq);
+    assert_eq!(42, result);
+    assert_eq!(7, calls.get());
+    assert_eq!(5, timing.samples.len());
+  }
+
+  #[test]
+  #[should_panic]
+  fn test_time_repeated_inconsistent() {
+    let calls = Cell::new(0);
+    let options = RunOptions { repeat: 3, ..RunOptions::default() };
+    time_repeated(&options, &|| {
+      calls.set(calls.get() + 1);
+      calls.get()
+    }, PartialEq::eq);
+  }
+
+  #[test]
+  fn test_parallel_map() {
+    let items: Vec<u64> = (0..100).collect();
+    let expected: Vec<u64> = items.iter().map(|x| x * x).collect();
+    assert_eq!(expected, parallel_map(1, &items, |x| x * x));
+    assert_eq!(expected, parallel_map(4, &items, |x| x * x));
+    assert_eq!(expected, parallel_map(1000, &items, |x| x * x));
+    assert!(parallel_map(4, &Vec::<u64>::new(), |x| x * x).is_empty());
+  }
+
+  #[test]
+  fn test_summarize() {
+    assert_eq!(Status::Fail, Status::summarize(&[Status::Pass, Status::Fail]));
+    assert_eq!(Status::Unknown, Status::summarize(&[Status::Unknown, Status::Pass]));
+    assert_eq!(Status::Pass, Status::summarize(&[Status::Skipped, Status::Pass]));
+    assert_eq!(Status::Skipped, Status::summarize(&[Status::Skipped, Status::Skipped]));
+  }
+
+  #[test]
+  fn test_isolation() {
+    let options = RunOptions { timeout: Some(Duration::from_millis(50)), ..RunOptions::default() };
+    let result = run_day::<Broken>("day0", "0", &options);
+    assert_eq!(Outcome::Panicked { phase: "part 1", message: "no zeros".to_string() },
+               result.outcome);
+    assert_eq!(vec![Status::Fail, Status::Fail], result.check(None));
+
+    let result = run_day::<Broken>("day0", "7", &options);
+    assert_eq!(Outcome::TimedOut { phase: "part 2", limit: Duration::from_millis(50) },
+               result.outcome);
+    assert_eq!(Some(Answer::Int(7)), result.part1.map(|(_, answer)| answer));
+    assert!(result.part2.is_none());
+
+    let options = RunOptions { parts: [true, false], ..RunOptions::default() };
+    assert_eq!(Outcome::Ok, run_day::<Broken>("day0", "7", &options).outcome);
+  }
+
+  #[test]
+  fn test_examples() {
+    for (i, examples) in crate::EXAMPLES.iter().enumerate() {
+      let declared = crate::PARAMS[i];
+      assert!(declared.iter().all(|(_, value, range)| range.contains(value)), "{}",
+              crate::NAMES[i]);
+      for example in examples.iter() {
+        assert!(example.params.iter().all(|(name, value)| {
+          declared.iter().any(|(n, _, range)| n == name && range.contains(value))
+        }), "{} {:?}", crate::NAMES[i], example.params);
+        let result = run_example(crate::FUNCS[i], example, &RunOptions::default());
+        assert_eq!(Outcome::Ok, result.outcome, "{}", crate::NAMES[i]);
+        assert!(!result.check(Some(&example.expected())).contains(&Status::Fail),
+                "{} gave {:?} for {:?}", crate::NAMES[i], result.get_answers(), example.answers);
+      }
+    }
+  }
+}
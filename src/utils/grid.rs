@@ -0,0 +1,332 @@
+//! A rectangular grid of cells and the points on it, for the puzzles that are
+//! laid out as a map.
+
+use crate::day::{self, ParseError};
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// A position on a grid, or the offset between two positions. x grows to the
+/// right and y grows down, the way the lines of an input are read.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point {
+  pub x: i32,
+  pub y: i32,
+}
+
+impl Point {
+  pub const UP: Point = Point::new(0, -1);
+  pub const DOWN: Point = Point::new(0, 1);
+  pub const LEFT: Point = Point::new(-1, 0);
+  pub const RIGHT: Point = Point::new(1, 0);
+  /// The four directions that share a side, clockwise from up
+  pub const ORTHOGONAL: [Point; 4] = [Point::UP, Point::RIGHT, Point::DOWN, Point::LEFT];
+  /// The eight directions that share a side or a corner, clockwise from up
+  pub const ALL: [Point; 8] = [Point::UP, Point::new(1, -1), Point::RIGHT, Point::new(1, 1),
+                               Point::DOWN, Point::new(-1, 1), Point::LEFT, Point::new(-1, -1)];
+
+  pub const fn new(x: i32, y: i32) -> Self {
+    Point { x, y }
+  }
+
+  /// Parse a point written as "x,y".
+  pub fn parse(text: &str) -> Result<Point, ParseError> {
+    let (x, y) = day::split_once(text, ",")?;
+    Ok(Point::new(day::parse(x)?, day::parse(y)?))
+  }
+
+  /// The direction of an arrow such as '^', as used for moves in the puzzles.
+  pub fn from_arrow(c: char) -> Option<Point> {
+    match c {
+      '^' => Some(Point::UP),
+      '>' => Some(Point::RIGHT),
+      'v' => Some(Point::DOWN),
+      '<' => Some(Point::LEFT),
+      _ => None,
+    }
+  }
+
+  /// This direction turned a quarter clockwise.
+  pub fn turn_right(self) -> Point {
+    Point::new(-self.y, self.x)
+  }
+
+  /// This direction turned a quarter anticlockwise.
+  pub fn turn_left(self) -> Point {
+    Point::new(self.y, -self.x)
+  }
+
+  pub fn manhattan(self, other: Point) -> u32 {
+    self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+  }
+
+  /// The four points that share a side with this one.
+  pub fn neighbors(self) -> impl Iterator<Item = Point> {
+    Point::ORTHOGONAL.into_iter().map(move |d| self + d)
+  }
+
+  /// The eight points that share a side or a corner with this one.
+  pub fn neighbors8(self) -> impl Iterator<Item = Point> {
+    Point::ALL.into_iter().map(move |d| self + d)
+  }
+}
+
+impl fmt::Display for Point {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{},{}", self.x, self.y)
+  }
+}
+
+impl Add for Point {
+  type Output = Point;
+
+  fn add(self, other: Point) -> Point {
+    Point::new(self.x + other.x, self.y + other.y)
+  }
+}
+
+impl Sub for Point {
+  type Output = Point;
+
+  fn sub(self, other: Point) -> Point {
+    Point::new(self.x - other.x, self.y - other.y)
+  }
+}
+
+impl AddAssign for Point {
+  fn add_assign(&mut self, other: Point) {
+    *self = *self + other;
+  }
+}
+
+impl SubAssign for Point {
+  fn sub_assign(&mut self, other: Point) {
+    *self = *self - other;
+  }
+}
+
+impl Mul<i32> for Point {
+  type Output = Point;
+
+  fn mul(self, factor: i32) -> Point {
+    Point::new(self.x * factor, self.y * factor)
+  }
+}
+
+impl Neg for Point {
+  type Output = Point;
+
+  fn neg(self) -> Point {
+    Point::new(-self.x, -self.y)
+  }
+}
+
+/// A rectangle of cells stored row by row, indexed by Point.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+  width: usize,
+  height: usize,
+  cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+  /// A grid with every cell set to value.
+  pub fn new(width: usize, height: usize, value: T) -> Self where T: Clone {
+    Grid { width, height, cells: vec![value; width * height] }
+  }
+
+  /// Parse a map with one row per line, converting each character with cell,
+  /// which returns None for a character that doesn't belong on the map.
+  pub fn parse(input: &str, cell: impl FnMut(char) -> Option<T>) -> Result<Self, ParseError> {
+    let (grid, []) = Self::parse_with_markers(input, [], cell)?;
+    Ok(grid)
+  }
+
+  /// Parse a map like parse does, also finding the markers, such as a start
+  /// and an end, that must each be on the map exactly once. The markers are
+  /// converted with cell too, since what's under a marker is part of the map.
+  pub fn parse_with_markers<const N: usize>(input: &str, markers: [char; N],
+                                            mut cell: impl FnMut(char) -> Option<T>)
+                                            -> Result<(Self, [Point; N]), ParseError> {
+    let mut found = [None; N];
+    let mut cells = Vec::new();
+    let mut width = None;
+    let mut height = 0;
+    for (y, line) in input.lines().enumerate() {
+      let mut columns = 0;
+      for (x, (i, c)) in line.char_indices().enumerate() {
+        if let Some(m) = markers.iter().position(|m| *m == c) {
+          if found[m].replace(Point::new(x as i32, y as i32)).is_some() {
+            return Err(ParseError::invalid(&line[i..], &format!("found more than one {c:?}")));
+          }
+        }
+        cells.push(cell(c).ok_or_else(|| ParseError::unexpected(&line[i..]))?);
+        columns += 1;
+      }
+      if *width.get_or_insert(columns) != columns {
+        return Err(ParseError::invalid(line, "rows have different lengths"));
+      }
+      height += 1;
+    }
+    let width = width.ok_or_else(|| ParseError::missing(input, "map"))?;
+    let mut points = [Point::default(); N];
+    for (point, (marker, found)) in points.iter_mut().zip(markers.iter().zip(found)) {
+      *point = found.ok_or_else(|| ParseError::missing(input, &format!("{marker:?}")))?;
+    }
+    Ok((Grid { width, height, cells }, points))
+  }
+
+  pub fn width(&self) -> usize {
+    self.width
+  }
+
+  pub fn height(&self) -> usize {
+    self.height
+  }
+
+  pub fn contains(&self, p: Point) -> bool {
+    p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+  }
+
+  pub fn get(&self, p: Point) -> Option<&T> {
+    self.contains(p).then(|| &self.cells[p.y as usize * self.width + p.x as usize])
+  }
+
+  pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+    if self.contains(p) {
+      Some(&mut self.cells[p.y as usize * self.width + p.x as usize])
+    } else {
+      None
+    }
+  }
+
+  /// Every point of the grid, row by row.
+  pub fn points(&self) -> impl Iterator<Item = Point> {
+    let width = self.width;
+    (0..self.cells.len()).map(move |i| Point::new((i % width) as i32, (i / width) as i32))
+  }
+
+  /// Every point of the grid with its cell, row by row.
+  pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+    self.points().zip(&self.cells)
+  }
+
+  /// The point of the first cell, row by row, that matches.
+  pub fn position(&self, mut matches: impl FnMut(&T) -> bool) -> Option<Point> {
+    self.iter().find(|(_, cell)| matches(cell)).map(|(p, _)| p)
+  }
+
+  /// The points that share a side with p and are on the grid.
+  pub fn neighbors(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+    p.neighbors().filter(|n| self.contains(*n))
+  }
+
+  /// The points that share a side or a corner with p and are on the grid.
+  pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+    p.neighbors8().filter(|n| self.contains(*n))
+  }
+
+  /// A grid of the same size with every cell converted.
+  pub fn map<U>(&self, convert: impl FnMut(&T) -> U) -> Grid<U> {
+    Grid { width: self.width, height: self.height, cells: self.cells.iter().map(convert).collect() }
+  }
+
+  /// Draw the grid with one character per cell, such as to show things that
+  /// are on top of the map.
+  pub fn render(&self, mut draw: impl FnMut(Point, &T) -> char) -> String {
+    let mut output = String::with_capacity((self.width + 1) * self.height);
+    for (p, cell) in self.iter() {
+      if p.x == 0 && p.y > 0 {
+        output.push('\n');
+      }
+      output.push(draw(p, cell));
+    }
+    output
+  }
+}
+
+impl<T> Index<Point> for Grid<T> {
+  type Output = T;
+
+  fn index(&self, p: Point) -> &T {
+    self.get(p).unwrap_or_else(|| panic!("{p} is outside of the grid"))
+  }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+  fn index_mut(&mut self, p: Point) -> &mut T {
+    self.get_mut(p).unwrap_or_else(|| panic!("{p} is outside of the grid"))
+  }
+}
+
+/// One line per row, with each cell written as it displays.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (p, cell) in self.iter() {
+      if p.x == 0 && p.y > 0 {
+        writeln!(f)?;
+      }
+      write!(f, "{cell}")?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Grid, Point};
+  use crate::day::{ParseError, ParseErrorKind};
+
+  const MAP: &str = "#S.\n.#E";
+
+  #[test]
+  fn test_point() {
+    assert_eq!(Point::new(3, 1), Point::new(1, 2) + Point::new(2, -1));
+    assert_eq!(Point::new(-2, 4), -Point::new(1, -2) * 2);
+    assert_eq!(Point::RIGHT, Point::UP.turn_right());
+    assert_eq!(Point::LEFT, Point::UP.turn_left());
+    assert_eq!(Point::UP, Point::LEFT.turn_right().turn_right().turn_right().turn_right().turn_right());
+    assert_eq!(5, Point::new(1, 1).manhattan(Point::new(-1, 4)));
+    assert_eq!(vec![Point::new(0, -1), Point::new(1, 0), Point::new(0, 1), Point::new(-1, 0)],
+               Point::default().neighbors().collect::<Vec<_>>());
+    assert_eq!(8, Point::default().neighbors8().count());
+    assert_eq!(Some(Point::DOWN), Point::from_arrow('v'));
+    assert_eq!("6,1", Point::new(6, 1).to_string());
+    assert_eq!(Ok(Point::new(-6, 1)), Point::parse("-6,1"));
+    assert!(Point::parse("6;1").is_err());
+  }
+
+  #[test]
+  fn test_parse() {
+    let (grid, [start, end]) = Grid::parse_with_markers(MAP, ['S', 'E'], |c| Some(c == '#')).unwrap();
+    assert_eq!((3, 2), (grid.width(), grid.height()));
+    assert_eq!((Point::new(1, 0), Point::new(2, 1)), (start, end));
+    assert!(grid[Point::new(1, 1)]);
+    assert!(!grid[start]);
+    assert_eq!(Some(Point::new(1, 1)), grid.iter().skip(1).find(|(_, wall)| **wall).map(|(p, _)| p));
+
+    let error = Grid::parse("#S.\n.#", Some).unwrap_err().locate("#S.\n.#");
+    assert_eq!((2, ParseErrorKind::Invalid("rows have different lengths".to_string())),
+               (error.line, error.kind));
+    let error = Grid::parse(MAP, |c| (c != 'E').then_some(c)).unwrap_err().locate(MAP);
+    assert_eq!((2, 3, ParseErrorKind::UnexpectedChar('E')), (error.line, error.column, error.kind));
+    let error = Grid::parse_with_markers(MAP, ['S', 'X'], Some).unwrap_err();
+    assert_eq!(ParseError::missing(MAP, "'X'"), error);
+    assert!(Grid::parse_with_markers("SS", ['S'], Some).is_err());
+  }
+
+  #[test]
+  fn test_grid() {
+    let mut grid = Grid::parse(MAP, Some).unwrap();
+    assert_eq!(MAP, grid.to_string());
+    assert_eq!(Some(&'E'), grid.get(Point::new(2, 1)));
+    assert_eq!(None, grid.get(Point::new(3, 0)));
+    assert_eq!(Some(Point::new(2, 1)), grid.position(|c| *c == 'E'));
+    assert_eq!(vec![Point::new(2, 0), Point::new(1, 1)],
+               grid.neighbors(Point::new(2, 1)).collect::<Vec<_>>());
+    assert_eq!(3, grid.neighbors8(Point::new(2, 1)).count());
+    grid[Point::new(0, 1)] = '@';
+    assert_eq!("#S.\n@#E", grid.to_string());
+    assert_eq!("#..\n.#.", grid.map(|c| *c == '#').render(|_, wall| if *wall { '#' } else { '.' }));
+    assert_eq!(Grid::new(3, 2, 0), grid.map(|_| 0));
+  }
+}
@@ -0,0 +1,420 @@
+//! Shortest path searches over any graph that can list the neighbors of its
+//! nodes: breadth first, Dijkstra and A*. A search keeps the distance of every
+//! node it reached and, unless only the distances were asked for, the
+//! predecessors of each node on its shortest paths, from which the paths can
+//! be rebuilt, counted or enumerated.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::utils::grid::{Grid, Point};
+
+/// A graph given by the steps that can be taken from each node.
+pub trait Graph {
+  type Node: Clone + Eq + Hash;
+  /// What a search keeps a value for each node it reached in, such as a
+  /// HashMap, or a Grid of the same size when the nodes are its points.
+  type Map<V: Clone>: NodeMap<Self::Node, V>;
+
+  /// An empty map for a search of the graph.
+  fn map<V: Clone>(&self) -> Self::Map<V>;
+
+  /// The nodes that are one step from node, with the cost of each step. The
+  /// costs must be positive for the predecessors to be complete.
+  fn neighbors(&self, node: &Self::Node) -> impl Iterator<Item = (Self::Node, u32)>;
+}
+
+/// A value for some of the nodes of a graph.
+pub trait NodeMap<N, V> {
+  fn get(&self, node: &N) -> Option<&V>;
+
+  fn get_mut(&mut self, node: &N) -> Option<&mut V>;
+
+  fn insert(&mut self, node: N, value: V);
+
+  /// Every node that has a value, with it, in no particular order.
+  fn iter<'a>(&'a self) -> impl Iterator<Item = (N, &'a V)> where V: 'a;
+}
+
+impl<N: Clone + Eq + Hash, V> NodeMap<N, V> for HashMap<N, V> {
+  fn get(&self, node: &N) -> Option<&V> {
+    HashMap::get(self, node)
+  }
+
+  fn get_mut(&mut self, node: &N) -> Option<&mut V> {
+    HashMap::get_mut(self, node)
+  }
+
+  fn insert(&mut self, node: N, value: V) {
+    HashMap::insert(self, node, value);
+  }
+
+  fn iter<'a>(&'a self) -> impl Iterator<Item = (N, &'a V)> where V: 'a {
+    HashMap::iter(self).map(|(node, value)| (node.clone(), value))
+  }
+}
+
+/// The values of the points of a grid, which are found without hashing.
+impl<V> NodeMap<Point, V> for Grid<Option<V>> {
+  fn get(&self, p: &Point) -> Option<&V> {
+    Grid::get(self, *p)?.as_ref()
+  }
+
+  fn get_mut(&mut self, p: &Point) -> Option<&mut V> {
+    Grid::get_mut(self, *p)?.as_mut()
+  }
+
+  fn insert(&mut self, p: Point, value: V) {
+    self[p] = Some(value);
+  }
+
+  fn iter<'a>(&'a self) -> impl Iterator<Item = (Point, &'a V)> where V: 'a {
+    Grid::iter(self).filter_map(|(p, value)| value.as_ref().map(|v| (p, v)))
+  }
+}
+
+/// The nodes that each node is reached from on its shortest paths. Most nodes
+/// are only reached from one, which is kept in a map of the graph's choosing.
+struct Predecessors<G: Graph> {
+  first: G::Map<G::Node>,
+  others: HashMap<G::Node, Vec<G::Node>>,
+}
+
+impl<G: Graph> Predecessors<G> {
+  fn new(graph: &G) -> Self {
+    Predecessors { first: graph.map(), others: HashMap::new() }
+  }
+
+  fn get(&self, node: &G::Node) -> impl Iterator<Item = &G::Node> {
+    let others = self.others.get(node).map_or(&[][..], Vec::as_slice);
+    self.first.get(node).into_iter().chain(others)
+  }
+}
+
+/// What a search of a graph found: the distance from the start of each node
+/// it reached, and the nodes that each one is reached from on its shortest
+/// paths, if they were tracked.
+pub struct Search<G: Graph> {
+  pub start: G::Node,
+  /// The goals that were reached at the least distance, in the order found
+  pub goals: Vec<G::Node>,
+  distances: G::Map<u32>,
+  predecessors: Option<Predecessors<G>>,
+}
+
+impl<G: Graph> Search<G> {
+  fn new(graph: &G, start: G::Node, track_predecessors: bool) -> Self {
+    let mut distances = graph.map();
+    distances.insert(start.clone(), 0);
+    let predecessors = track_predecessors.then(|| Predecessors::new(graph));
+    Search { start, goals: Vec::new(), distances, predecessors }
+  }
+
+  /// Record that next can be reached from node at a distance, returning true
+  /// if that's shorter than before, so that next has to be visited.
+  fn relax(&mut self, node: &G::Node, next: G::Node, distance: u32) -> bool {
+    match self.distances.get(&next) {
+      Some(&known) if known < distance => false,
+      Some(&known) if known == distance => {
+        if let Some(predecessors) = &mut self.predecessors {
+          predecessors.others.entry(next).or_default().push(node.clone());
+        }
+        false
+      }
+      _ => {
+        if let Some(predecessors) = &mut self.predecessors {
+          predecessors.first.insert(next.clone(), node.clone());
+          // Ties are rare, so don't hash next when there are none to forget
+          if !predecessors.others.is_empty() {
+            predecessors.others.remove(&next);
+          }
+        }
+        self.distances.insert(next, distance);
+        true
+      }
+    }
+  }
+
+  pub fn distance(&self, node: &G::Node) -> Option<u32> {
+    self.distances.get(node).copied()
+  }
+
+  /// The distance of the nearest goal, if one was reached.
+  pub fn goal_distance(&self) -> Option<u32> {
+    self.goals.first().and_then(|goal| self.distance(goal))
+  }
+
+  /// Every node that was reached, the start included.
+  pub fn reached(&self) -> impl Iterator<Item = G::Node> + '_ {
+    self.distances.iter().map(|(node, _)| node)
+  }
+
+  /// The nodes just before node on its shortest paths, which the start has
+  /// none of. Panics if the search only kept distances.
+  pub fn predecessors(&self, node: &G::Node) -> impl Iterator<Item = &G::Node> + '_ {
+    let predecessors = self.predecessors.as_ref().expect("the search didn't track predecessors");
+    predecessors.get(node)
+  }
+
+  /// One of the shortest paths from the start to node, both included.
+  pub fn path(&self, node: &G::Node) -> Option<Vec<G::Node>> {
+    self.distances.get(node)?;
+    let mut path = vec![node.clone()];
+    while let Some(previous) = self.predecessors(path.last().unwrap()).next() {
+      path.push(previous.clone());
+    }
+    path.reverse();
+    Some(path)
+  }
+
+  /// Every shortest path from the start to node. There can be exponentially
+  /// many of them, see count_paths for when only their number is needed.
+  pub fn all_paths(&self, node: &G::Node) -> Vec<Vec<G::Node>> {
+    if self.distances.get(node).is_none() {
+      return Vec::new();
+    }
+    // Walk back from node, each partial path being reversed
+    let mut paths = Vec::new();
+    let mut stack = vec![vec![node.clone()]];
+    while let Some(path) = stack.pop() {
+      let mut predecessors = self.predecessors(path.last().unwrap()).peekable();
+      if predecessors.peek().is_none() {
+        paths.push(path.into_iter().rev().collect());
+        continue;
+      }
+      for previous in predecessors {
+        let mut longer = path.clone();
+        longer.push(previous.clone());
+        stack.push(longer);
+      }
+    }
+    paths
+  }
+
+  /// The number of shortest paths from the start to node.
+  pub fn count_paths(&self, node: &G::Node) -> u64 {
+    let Some(&distance) = self.distances.get(node) else { return 0 };
+    // Predecessors are nearer the start, so count in order of distance
+    let mut nodes: Vec<(G::Node, u32)> = self.distances.iter()
+      .filter(|(_, d)| **d <= distance)
+      .map(|(n, d)| (n, *d))
+      .collect();
+    nodes.sort_by_key(|(_, d)| *d);
+    let mut counts: HashMap<G::Node, u64> = HashMap::new();
+    for (n, _) in nodes {
+      let count = if n == self.start {
+        1
+      } else {
+        self.predecessors(&n).map(|p| counts.get(p).copied().unwrap_or(0)).sum()
+      };
+      counts.insert(n, count);
+    }
+    counts[node]
+  }
+
+  /// Every node that's on a shortest path from the start to one of the
+  /// targets, the start and the targets included.
+  pub fn on_shortest_paths<'a>(&'a self, targets: impl IntoIterator<Item = &'a G::Node>)
+                               -> HashSet<G::Node> {
+    let mut on_paths = HashSet::new();
+    let mut stack: Vec<&G::Node> = targets.into_iter()
+      .filter(|t| self.distances.get(t).is_some())
+      .collect();
+    while let Some(node) = stack.pop() {
+      if on_paths.insert(node.clone()) {
+        stack.extend(self.predecessors(node));
+      }
+    }
+    on_paths
+  }
+}
+
+/// Search breadth first from start, counting every step as 1 whatever its
+/// cost, until the nearest goals are reached or there is nowhere left to go.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node, is_goal: impl FnMut(&G::Node) -> bool)
+                     -> Search<G> {
+  breadth_first(graph, start, is_goal, true)
+}
+
+/// Search breadth first like bfs, keeping only the distances, for when the
+/// paths themselves aren't needed.
+pub fn bfs_distances<G: Graph>(graph: &G, start: G::Node, is_goal: impl FnMut(&G::Node) -> bool)
+                               -> Search<G> {
+  breadth_first(graph, start, is_goal, false)
+}
+
+fn breadth_first<G: Graph>(graph: &G, start: G::Node, mut is_goal: impl FnMut(&G::Node) -> bool,
+                           track_predecessors: bool) -> Search<G> {
+  let mut search = Search::new(graph, start.clone(), track_predecessors);
+  let mut queue = VecDeque::from([(start, 0)]);
+  while let Some((node, distance)) = queue.pop_front() {
+    if search.goal_distance().is_some_and(|goal| distance > goal) {
+      break;
+    }
+    if is_goal(&node) {
+      search.goals.push(node);
+      continue;
+    }
+    for (next, _) in graph.neighbors(&node) {
+      if search.relax(&node, next.clone(), distance + 1) {
+        queue.push_back((next, distance + 1));
+      }
+    }
+  }
+  search
+}
+
+/// Search from start in order of distance until the nearest goals are
+/// reached or there is nowhere left to go.
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node, is_goal: impl FnMut(&G::Node) -> bool)
+                          -> Search<G> {
+  astar(graph, start, is_goal, |_| 0)
+}
+
+/// A node waiting to be visited, ordered so that the heap pops the lowest
+/// estimate first.
+struct Entry<N> {
+  estimate: u32,
+  node: N,
+}
+
+impl<N> PartialEq for Entry<N> {
+  fn eq(&self, other: &Self) -> bool {
+    self.estimate == other.estimate
+  }
+}
+
+impl<N> Eq for Entry<N> {}
+
+impl<N> PartialOrd for Entry<N> {
+  fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<N> Ord for Entry<N> {
+  fn cmp(&self, other: &Self) -> Ordering {
+    other.estimate.cmp(&self.estimate)
+  }
+}
+
+/// Search from start like Dijkstra, guided by a heuristic that estimates the
+/// distance from a node to the nearest goal. The heuristic must never
+/// overestimate, nor drop by more than the cost of a step, for the distances
+/// to the goals and their predecessors to be right.
+pub fn astar<G: Graph>(graph: &G, start: G::Node, mut is_goal: impl FnMut(&G::Node) -> bool,
+                       heuristic: impl Fn(&G::Node) -> u32) -> Search<G> {
+  let mut search = Search::new(graph, start.clone(), true);
+  let mut visited = HashSet::new();
+  let mut heap = BinaryHeap::from([Entry { estimate: heuristic(&start), node: start }]);
+  while let Some(Entry { estimate, node }) = heap.pop() {
+    // Keep going while there may be other goals, or steps to them, as near
+    if search.goal_distance().is_some_and(|goal| estimate > goal) {
+      break;
+    }
+    if !visited.insert(node.clone()) {
+      continue;
+    }
+    if is_goal(&node) {
+      search.goals.push(node);
+      continue;
+    }
+    let distance = search.distance(&node).unwrap();
+    for (next, cost) in graph.neighbors(&node) {
+      if search.relax(&node, next.clone(), distance + cost) {
+        heap.push(Entry { estimate: distance + cost + heuristic(&next), node: next });
+      }
+    }
+  }
+  search
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{astar, bfs, bfs_distances, dijkstra, Graph};
+  use crate::utils::grid::{Grid, Point};
+
+  // Two ways around the wall in the middle, and a slow cell on one of them
+  const MAP: &str = "S.2\n.#.\n..E";
+
+  struct Map(Grid<char>);
+
+  impl Graph for Map {
+    type Node = Point;
+    type Map<V: Clone> = Grid<Option<V>>;
+
+    fn map<V: Clone>(&self) -> Grid<Option<V>> {
+      Grid::new(self.0.width(), self.0.height(), None)
+    }
+
+    fn neighbors(&self, p: &Point) -> impl Iterator<Item = (Point, u32)> {
+      self.0.neighbors(*p)
+        .filter(|n| self.0[*n] != '#')
+        .map(|n| (n, self.0[n].to_digit(10).unwrap_or(1)))
+    }
+  }
+
+  fn map() -> (Map, Point, Point) {
+    let (grid, [start, end]) = Grid::parse_with_markers(MAP, ['S', 'E'], Some).unwrap();
+    (Map(grid), start, end)
+  }
+
+  #[test]
+  fn test_bfs() {
+    let (map, start, end) = map();
+    let search = bfs(&map, start, |p| *p == end);
+    assert_eq!(Some(4), search.goal_distance());
+    assert_eq!(2, search.count_paths(&end));
+    assert_eq!(2, search.predecessors(&end).count());
+    assert_eq!(Some(5), search.path(&end).map(|p| p.len()));
+    let mut paths = search.all_paths(&end);
+    paths.sort();
+    assert_eq!(vec![start, Point::new(0, 1), Point::new(0, 2), Point::new(1, 2), end], paths[0]);
+    assert_eq!(vec![start, Point::new(1, 0), Point::new(2, 0), Point::new(2, 1), end], paths[1]);
+    assert_eq!(8, search.on_shortest_paths([&end]).len());
+  }
+
+  #[test]
+  fn test_bfs_distances() {
+    let (map, start, end) = map();
+    let search = bfs_distances(&map, start, |p| *p == end);
+    assert_eq!(Some(4), search.goal_distance());
+    assert_eq!(Some(2), search.distance(&Point::new(2, 0)));
+    assert_eq!(None, search.distance(&Point::new(1, 1)));
+  }
+
+  #[test]
+  #[should_panic(expected = "the search didn't track predecessors")]
+  fn test_bfs_distances_without_paths() {
+    let (map, start, end) = map();
+    bfs_distances(&map, start, |p| *p == end).path(&end);
+  }
+
+  #[test]
+  fn test_dijkstra() {
+    let (map, start, end) = map();
+    let search = dijkstra(&map, start, |p| *p == end);
+    assert_eq!(Some(4), search.goal_distance());
+    assert_eq!(1, search.count_paths(&end));
+    assert_eq!(Some(vec![start, Point::new(0, 1), Point::new(0, 2), Point::new(1, 2), end]),
+               search.path(&end));
+    assert!(!search.on_shortest_paths([&end]).contains(&Point::new(2, 0)));
+    // Without a goal everything is reached
+    let search = dijkstra(&map, start, |_| false);
+    assert_eq!(Some(3), search.distance(&Point::new(2, 0)));
+    assert_eq!(8, search.reached().count());
+    assert!(search.goals.is_empty());
+  }
+
+  #[test]
+  fn test_astar() {
+    let (map, start, end) = map();
+    let search = astar(&map, start, |p| *p == end, |p| p.manhattan(end));
+    assert_eq!(vec![end], search.goals);
+    assert_eq!(Some(4), search.goal_distance());
+    assert_eq!(1, search.count_paths(&end));
+    assert_eq!(None, search.path(&Point::new(1, 1)));
+    assert_eq!(0, search.count_paths(&Point::new(1, 1)));
+  }
+}
@@ -0,0 +1,125 @@
+//! Support for `--watch`, which runs the selected days again whenever one of
+//! the files they read changes. The files are polled, which works the same
+//! everywhere and is cheap for the handful of files a run reads.
+
+use crate::utils::{DayResult, Outcome, Timing};
+use std::fs;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// The modification times of a set of files, None for a file that's missing.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Snapshot {
+  times: Vec<Option<SystemTime>>,
+}
+
+impl Snapshot {
+  pub fn take(paths: &[String]) -> Self {
+    Snapshot { times: paths.iter().map(|p| fs::metadata(p).and_then(|m| m.modified()).ok()).collect() }
+  }
+
+  /// The paths whose files were created, removed or modified between the
+  /// snapshots.
+  fn changed<'a>(&self, later: &Snapshot, paths: &'a [String]) -> Vec<&'a str> {
+    paths.iter().zip(self.times.iter().zip(&later.times))
+      .filter(|(_, (before, after))| before != after)
+      .map(|(path, _)| path.as_str())
+      .collect()
+  }
+}
+
+/// Poll the files every interval until one of them changes and they have
+/// then stayed the same for an interval, so that a file which is still being
+/// written isn't read half way. Returns the changed files and their snapshot.
+pub fn wait_for_change(paths: &[String], since: &Snapshot, interval: Duration)
+                       -> (Vec<String>, Snapshot) {
+  let mut latest = since.clone();
+  loop {
+    thread::sleep(interval);
+    let now = Snapshot::take(paths);
+    if now == latest && now != *since {
+      let changed = since.changed(&now, paths).into_iter().map(String::from).collect();
+      return (changed, now);
+    }
+    latest = now;
+  }
+}
+
+/// How the median time of a phase moved.
+fn timing_change(before: &Timing, after: &Timing) -> String {
+  let (before, after) = (before.median(), after.median());
+  if before.is_zero() {
+    return format!("{after:.2?} (was {before:.2?})");
+  }
+  let percent = (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0;
+  format!("{after:.2?} (was {before:.2?}, {percent:+.1}%)")
+}
+
+/// Describe how the answers and timings of each day moved since its previous
+/// run, one line per phase that ran in either.
+pub fn differences(previous: &[DayResult], current: &[DayResult]) -> Vec<String> {
+  let mut lines = Vec::new();
+  for r in current {
+    let Some(p) = previous.iter().find(|p| p.day == r.day) else { continue };
+    let day = r.pretty_day();
+    if p.outcome == Outcome::Ok && r.outcome == Outcome::Ok {
+      lines.push(format!("{day} generator: {}", timing_change(&p.generate_time, &r.generate_time)));
+    }
+    for (part, before, after) in [(1, &p.part1, &r.part1), (2, &p.part2, &r.part2)] {
+      let line = match (before, after) {
+        (Some((t0, a0)), Some((t1, a1))) if a0 == a1 =>
+          format!("{a1} unchanged in {}", timing_change(t0, t1)),
+        (Some((t0, a0)), Some((t1, a1))) => format!("{a1} (was {a0}) in {}", timing_change(t0, t1)),
+        (Some((_, a0)), None) => format!("no answer (was {a0})"),
+        (None, Some((t1, a1))) => format!("{a1} in {:.2?} (was no answer)", t1.median()),
+        (None, None) => continue,
+      };
+      lines.push(format!("{day} part {part}: {line}"));
+    }
+  }
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{differences, Snapshot};
+  use crate::answer::Answer;
+  use crate::utils::{DayResult, Outcome, Timing};
+  use std::time::Duration;
+
+  fn result(part1: &str, part1_time: u64, part2: Option<&str>) -> DayResult {
+    DayResult {
+      day: "day14".to_string(),
+      outcome: Outcome::Ok,
+      generate_time: Timing::from(Duration::from_millis(1)),
+      part1: Some((Timing::from(Duration::from_millis(part1_time)), Answer::parse(part1))),
+      part2: part2.map(|a| (Timing::from(Duration::from_millis(2)), Answer::parse(a))),
+      allocs: [None; 3],
+    }
+  }
+
+  #[test]
+  fn test_differences() {
+    let lines = differences(&[result("12", 10, Some("7"))], &[result("13", 5, None)]);
+    assert_eq!(vec!["Day 14 generator: 1.00ms (was 1.00ms, +0.0%)",
+                    "Day 14 part 1: 13 (was 12) in 5.00ms (was 10.00ms, -50.0%)",
+                    "Day 14 part 2: no answer (was 7)"], lines);
+    let lines = differences(&[result("12", 10, None)], &[result("12", 10, None)]);
+    assert_eq!("Day 14 part 1: 12 unchanged in 10.00ms (was 10.00ms, +0.0%)", lines[1]);
+    assert!(differences(&[], &[result("12", 10, None)]).is_empty());
+  }
+
+  #[test]
+  fn test_snapshot() {
+    let dir = std::env::temp_dir().join(format!("aoc2024-watch-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let paths = vec![dir.join("a.txt").to_string_lossy().to_string(),
+                     dir.join("b.txt").to_string_lossy().to_string()];
+    let before = Snapshot::take(&paths);
+    std::fs::write(&paths[1], "1").unwrap();
+    let after = Snapshot::take(&paths);
+    assert_eq!(vec![paths[1].as_str()], before.changed(&after, &paths));
+    assert!(after.changed(&after, &paths).is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+}